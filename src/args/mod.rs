@@ -0,0 +1,349 @@
+use crate::{cli, env, UserProfile};
+
+pub static USAGE: &str = "\
+Usage:
+  compose-tui                                   open the interactive menu
+  compose-tui up <profile> [services...]        build and start services
+  compose-tui down <profile> [--rmi local|all] [--volumes]
+                   [--remove-orphans] [--timeout <seconds>]
+                                                stop and remove the stack
+  compose-tui start <profile> [services...]     start existing services
+  compose-tui stop <profile> [services...]      stop running services
+  compose-tui restart <profile> [services...]   restart services
+  compose-tui pull <profile> [services...]      pull service images
+  compose-tui build <profile> [--no-cache] [services...]
+                                                build service images
+  compose-tui ps <profile> [services...]        list containers";
+
+#[derive(Debug, PartialEq)]
+pub enum Subcommand {
+    Help,
+    Up {
+        profile: String,
+        services: Vec<String>,
+    },
+    Down {
+        profile: String,
+        options: cli::DownOptions,
+    },
+    Start {
+        profile: String,
+        services: Vec<String>,
+    },
+    Stop {
+        profile: String,
+        services: Vec<String>,
+    },
+    Restart {
+        profile: String,
+        services: Vec<String>,
+    },
+    Pull {
+        profile: String,
+        services: Vec<String>,
+    },
+    Build {
+        profile: String,
+        services: Vec<String>,
+        no_cache: bool,
+    },
+    Ps {
+        profile: String,
+        services: Vec<String>,
+    },
+}
+
+/// Parses the arguments after the binary name. `Ok(None)` means no
+/// subcommand was given and the TUI should start.
+pub fn parse(args: &[String]) -> Result<Option<Subcommand>, String> {
+    let (name, rest) = match args.split_first() {
+        Some(split) => split,
+        None => return Ok(None),
+    };
+    if let "-h" | "--help" | "help" = name.as_str() {
+        return Ok(Some(Subcommand::Help));
+    }
+
+    let (profile, rest) = match rest.split_first() {
+        Some((profile, rest)) if !profile.starts_with('-') => (profile.clone(), rest),
+        _ => return Err(format!("`{}` needs a profile", name)),
+    };
+
+    let services = |rest: &[String]| -> Result<Vec<String>, String> {
+        match rest.iter().find(|a| a.starts_with('-')) {
+            Some(flag) => Err(format!("unknown option `{}` for `{}`", flag, name)),
+            None => Ok(rest.to_vec()),
+        }
+    };
+
+    let subcommand = match name.as_str() {
+        "up" => Subcommand::Up {
+            profile,
+            services: services(rest)?,
+        },
+        "start" => Subcommand::Start {
+            profile,
+            services: services(rest)?,
+        },
+        "stop" => Subcommand::Stop {
+            profile,
+            services: services(rest)?,
+        },
+        "restart" => Subcommand::Restart {
+            profile,
+            services: services(rest)?,
+        },
+        "pull" => Subcommand::Pull {
+            profile,
+            services: services(rest)?,
+        },
+        "ps" => Subcommand::Ps {
+            profile,
+            services: services(rest)?,
+        },
+        "build" => {
+            let no_cache = rest.iter().any(|a| a == "--no-cache");
+            let rest: Vec<String> = rest
+                .iter()
+                .filter(|a| *a != "--no-cache")
+                .cloned()
+                .collect();
+            Subcommand::Build {
+                profile,
+                services: services(&rest)?,
+                no_cache,
+            }
+        }
+        "down" => Subcommand::Down {
+            profile,
+            options: parse_down(rest)?,
+        },
+        _ => return Err(format!("unknown command `{}`", name)),
+    };
+    Ok(Some(subcommand))
+}
+
+fn parse_down(args: &[String]) -> Result<cli::DownOptions, String> {
+    let mut options = cli::DownOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_owned())),
+            None => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| format!("`{}` needs a value", flag))
+        };
+        match flag {
+            "--rmi" => {
+                let rmi = value()?;
+                match rmi.as_str() {
+                    "local" | "all" => options.rmi = Some(rmi),
+                    _ => return Err(format!("--rmi must be `local` or `all`, got `{}`", rmi)),
+                }
+            }
+            "-t" | "--timeout" => {
+                let timeout = value()?;
+                options.timeout = Some(
+                    timeout
+                        .parse()
+                        .map_err(|_| format!("--timeout must be a number, got `{}`", timeout))?,
+                );
+            }
+            "-v" | "--volumes" if inline.is_none() => options.volumes = true,
+            "--remove-orphans" if inline.is_none() => options.remove_orphans = true,
+            _ => return Err(format!("unexpected argument `{}` for `down`", arg)),
+        }
+    }
+    Ok(options)
+}
+
+/// Runs `subcommand` against its profile, printing compose output as it
+/// arrives, and returns the exit code for the process.
+pub fn run(subcommand: Subcommand) -> i32 {
+    let (profile, receiver) = match subcommand {
+        Subcommand::Help => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Subcommand::Up { profile, services } => {
+            let user_profile = user_profile(&profile);
+            let executor = cli::executor::from_profile(&user_profile);
+            let options = &user_profile.config.up;
+            (
+                profile,
+                cli::up(executor.as_ref(), Some(services.join(" ")), options),
+            )
+        }
+        Subcommand::Down { profile, options } => {
+            let executor = executor(&profile);
+            (profile, cli::down(executor.as_ref(), &options))
+        }
+        Subcommand::Start { profile, services } => {
+            let executor = executor(&profile);
+            (
+                profile,
+                cli::start(executor.as_ref(), Some(services.join(" "))),
+            )
+        }
+        Subcommand::Stop { profile, services } => {
+            let executor = executor(&profile);
+            (
+                profile,
+                cli::stop(executor.as_ref(), Some(services.join(" "))),
+            )
+        }
+        Subcommand::Restart { profile, services } => {
+            let executor = executor(&profile);
+            (
+                profile,
+                cli::restart(executor.as_ref(), Some(services.join(" "))),
+            )
+        }
+        Subcommand::Pull { profile, services } => {
+            let executor = executor(&profile);
+            (
+                profile,
+                cli::pull(executor.as_ref(), Some(services.join(" "))),
+            )
+        }
+        Subcommand::Build {
+            profile,
+            services,
+            no_cache,
+        } => {
+            let executor = executor(&profile);
+            (
+                profile,
+                cli::build(executor.as_ref(), Some(services.join(" ")), no_cache),
+            )
+        }
+        Subcommand::Ps { profile, services } => {
+            let executor = executor(&profile);
+            (
+                profile,
+                cli::ps(executor.as_ref(), Some(services.join(" "))),
+            )
+        }
+    };
+
+    for output in receiver.iter() {
+        match output {
+            cli::Output::Stdout(line) => println!("{}", line),
+            cli::Output::Stderr(line) => eprintln!("{}", line),
+            cli::Output::Exit(result) => {
+                if !result.success() {
+                    eprintln!(
+                        "{}: command failed after {:.1}s",
+                        profile,
+                        result.duration.as_secs_f32()
+                    );
+                }
+                return result.exit_code();
+            }
+        }
+    }
+    1
+}
+
+fn user_profile(name: &str) -> UserProfile {
+    let config = match env::load(name.to_owned()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        }
+    };
+    let mut user_profile = UserProfile::default();
+    user_profile.set(name.to_owned(), config);
+    user_profile
+}
+
+fn executor(name: &str) -> Box<dyn cli::Executor> {
+    cli::executor::from_profile(&user_profile(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn no_arguments_starts_the_tui() {
+        assert_eq!(parse(&[]), Ok(None));
+    }
+
+    #[test]
+    fn up_takes_services() {
+        assert_eq!(
+            parse(&args("up prod web worker")),
+            Ok(Some(Subcommand::Up {
+                profile: String::from("prod"),
+                services: args("web worker"),
+            }))
+        );
+    }
+
+    #[test]
+    fn down_takes_rmi() {
+        let expected = Ok(Some(Subcommand::Down {
+            profile: String::from("prod"),
+            options: cli::DownOptions {
+                rmi: Some(String::from("local")),
+                ..cli::DownOptions::default()
+            },
+        }));
+        assert_eq!(parse(&args("down prod --rmi local")), expected);
+        assert_eq!(parse(&args("down prod --rmi=local")), expected);
+        assert!(parse(&args("down prod --rmi none")).is_err());
+        assert!(parse(&args("down prod --rmi")).is_err());
+        assert!(parse(&args("down prod web")).is_err());
+    }
+
+    #[test]
+    fn down_takes_cleanup_options() {
+        assert_eq!(
+            parse(&args("down prod -v --remove-orphans --timeout=5")),
+            Ok(Some(Subcommand::Down {
+                profile: String::from("prod"),
+                options: cli::DownOptions {
+                    volumes: true,
+                    remove_orphans: true,
+                    timeout: Some(5),
+                    ..cli::DownOptions::default()
+                },
+            }))
+        );
+        assert!(parse(&args("down prod --timeout soon")).is_err());
+    }
+
+    #[test]
+    fn build_takes_no_cache_anywhere() {
+        assert_eq!(
+            parse(&args("build prod web --no-cache")),
+            Ok(Some(Subcommand::Build {
+                profile: String::from("prod"),
+                services: args("web"),
+                no_cache: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn profile_is_required() {
+        assert!(parse(&args("stop")).is_err());
+        assert!(parse(&args("start --rmi")).is_err());
+    }
+
+    #[test]
+    fn unknown_input_is_rejected() {
+        assert!(parse(&args("exec prod")).is_err());
+        assert!(parse(&args("up prod --force")).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::env::Profile;
+
+/// Argument list for a single `docker compose` invocation.
+#[derive(Debug)]
+pub struct ComposeCommand {
+    args: Vec<String>,
+}
+
+impl ComposeCommand {
+    pub fn new(subcommand: &str) -> ComposeCommand {
+        ComposeCommand {
+            args: vec![subcommand.to_owned()],
+        }
+    }
+
+    pub fn arg(mut self, arg: &str) -> ComposeCommand {
+        self.args.push(arg.to_owned());
+        self
+    }
+
+    /// Appends every service named in `target` as its own argument.
+    pub fn services(mut self, target: &str) -> ComposeCommand {
+        self.args.extend(services(target));
+        self
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+}
+
+/// Options given before the subcommand of every compose invocation for
+/// `config`: compose files, project name, compose profiles and env files.
+pub fn global_args(config: &Profile) -> Vec<String> {
+    let mut args = Vec::new();
+    for file in &config.compose_files {
+        args.extend([String::from("-f"), file.clone()]);
+    }
+    if let Some(project_name) = &config.project_name {
+        args.extend([String::from("-p"), project_name.clone()]);
+    }
+    for profile in &config.compose_profiles {
+        args.extend([String::from("--profile"), profile.clone()]);
+    }
+    for env_file in &config.env_files {
+        args.extend([String::from("--env-file"), env_file.clone()]);
+    }
+    args
+}
+
+/// Splits a free-text target such as `"web, worker db"` into service names.
+pub fn services(target: &str) -> Vec<String> {
+    target
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Quotes `arg` so a POSIX shell reads it back as exactly one word.
+pub fn quote(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_-./=:@,+%".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        return arg.to_owned();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Builds the shell line run on the remote host. A leading `~/` in `path` is
+/// left unquoted so the remote shell still expands it.
+pub fn remote_script(path: &str, args: &[String]) -> String {
+    format!(
+        "cd {} && docker compose {}",
+        remote_path(path),
+        quote_all(args)
+    )
+}
+
+/// Like [`remote_script`], but compose is stopped once the ssh connection
+/// closes. Without a terminal the remote side gets no hangup when the client
+/// goes away, so compose runs in the background while `sh` waits for stdin,
+/// which the client keeps open, to end.
+pub fn stoppable_script(path: &str, args: &[String]) -> String {
+    let script = format!(
+        "cd {} && exec docker compose {} & pid=$!; (cat; kill $pid) >/dev/null 2>&1 & wait $pid",
+        remote_path(path),
+        quote_all(args)
+    );
+    format!("sh -c {}", quote(&script))
+}
+
+fn remote_path(path: &str) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("~/{}", quote(rest)),
+        None => quote(path),
+    }
+}
+
+fn quote_all(args: &[String]) -> String {
+    let args: Vec<String> = args.iter().map(|a| quote(a)).collect();
+    args.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn services_are_split_on_whitespace_and_commas() {
+        assert_eq!(services(" web,worker  db\t"), vec!["web", "worker", "db"]);
+        assert!(services("").is_empty());
+    }
+
+    #[test]
+    fn plain_words_are_left_alone() {
+        assert_eq!(quote("web"), "web");
+        assert_eq!(quote("--rmi"), "--rmi");
+        assert_eq!(quote("/srv/app-1"), "/srv/app-1");
+    }
+
+    #[test]
+    fn hostile_words_are_quoted() {
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("a b"), "'a b'");
+        assert_eq!(quote("web; rm -rf /"), "'web; rm -rf /'");
+        assert_eq!(quote("$(reboot)"), "'$(reboot)'");
+        assert_eq!(quote("`id`"), "'`id`'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote("a\nb"), "'a\nb'");
+    }
+
+    #[test]
+    fn hostile_target_cannot_escape_its_arguments() {
+        let command = ComposeCommand::new("start").services("web;reboot $(id) `id`");
+        assert_eq!(
+            remote_script("/srv/my app", command.args()),
+            "cd '/srv/my app' && docker compose start 'web;reboot' '$(id)' '`id`'"
+        );
+    }
+
+    #[test]
+    fn global_args_come_from_the_profile() {
+        let config = Profile {
+            compose_files: vec![
+                String::from("docker-compose.yml"),
+                String::from("docker-compose.prod.yml"),
+            ],
+            project_name: Some(String::from("shop")),
+            compose_profiles: vec![String::from("workers")],
+            env_files: vec![String::from(".env.prod")],
+            ..Profile::default()
+        };
+        assert_eq!(
+            global_args(&config).join(" "),
+            "-f docker-compose.yml -f docker-compose.prod.yml -p shop --profile workers --env-file .env.prod"
+        );
+        assert!(global_args(&Profile::default()).is_empty());
+    }
+
+    #[test]
+    fn home_relative_path_keeps_tilde() {
+        assert_eq!(
+            remote_script("~/stack; id", &[String::from("ps")]),
+            "cd ~/'stack; id' && docker compose ps"
+        );
+    }
+
+    /// Runs `stoppable_script` the way sshd would, with a `docker` that
+    /// prints its pid and then sleeps or exits.
+    fn run_stoppable(
+        docker: &str,
+        stdin: std::process::Stdio,
+    ) -> (std::process::Child, std::path::PathBuf) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!(
+            "compose-tui-stoppable-{}-{}",
+            std::process::id(),
+            docker.len()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let fake = dir.join("docker");
+        std::fs::write(&fake, format!("#!/bin/sh\necho $$\n{}\n", docker)).unwrap();
+        std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path = format!("{}:{}", dir.display(), std::env::var("PATH").unwrap());
+        let child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(stoppable_script("/", &[String::from("up")]))
+            .env("PATH", path)
+            .stdin(stdin)
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        (child, dir)
+    }
+
+    #[test]
+    fn stoppable_script_keeps_the_exit_code() {
+        let (mut child, dir) = run_stoppable("exit 3", std::process::Stdio::piped());
+        // Held open, as the ssh client does, until compose has exited.
+        let stdin = child.stdin.take();
+        assert_eq!(child.wait().unwrap().code(), Some(3));
+        drop(stdin);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn stoppable_script_stops_compose_when_stdin_closes() {
+        use std::io::{BufRead, BufReader};
+
+        let (mut child, dir) = run_stoppable("exec sleep 30", std::process::Stdio::piped());
+        let mut pid = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut pid)
+            .unwrap();
+        drop(child.stdin.take());
+        child.wait().unwrap();
+
+        let alive = || {
+            std::process::Command::new("kill")
+                .args(["-0", pid.trim()])
+                .stderr(std::process::Stdio::null())
+                .status()
+                .unwrap()
+                .success()
+        };
+        let started = std::time::Instant::now();
+        while alive() && started.elapsed() < std::time::Duration::from_secs(5) {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        assert!(!alive());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
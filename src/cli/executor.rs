@@ -0,0 +1,392 @@
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{command, multiplex, wake, CommandResult, Job, Output};
+use crate::{env, UserProfile};
+
+/// Runs `docker compose` for a profile and streams back what it prints.
+pub trait Executor {
+    fn compose(&self, args: &[String]) -> Job;
+
+    /// Runs compose attached to this terminal and waits for it to exit.
+    fn interactive(&self, args: &[String]) -> io::Result<Option<i32>>;
+}
+
+/// Runs compose on a remote host over `ssh`.
+#[derive(Debug, Default)]
+pub struct Ssh {
+    pub username: String,
+    pub hostname: String,
+    pub path: String,
+    pub port: Option<u16>,
+    pub identity_file: Option<String>,
+    pub jump_host: Option<String>,
+    pub options: Vec<String>,
+    /// Socket of the shared master connection, see [`multiplex`].
+    pub control_path: Option<PathBuf>,
+    /// Compose options given before every subcommand.
+    pub compose_args: Vec<String>,
+    /// Fail instead of prompting, see [`set_batch_mode`].
+    pub batch_mode: bool,
+}
+
+impl Ssh {
+    /// Arguments for `ssh` up to and including the destination.
+    fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(control_path) = &self.control_path {
+            args.extend([
+                String::from("-o"),
+                String::from("ControlMaster=no"),
+                String::from("-o"),
+                format!("ControlPath={}", control_path.display()),
+            ]);
+        }
+        args.extend(self.connection_args());
+        args
+    }
+
+    /// Arguments for commands that are not attached to the terminal.
+    fn batch_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.batch_mode {
+            args.extend([String::from("-o"), String::from("BatchMode=yes")]);
+        }
+        args.extend(self.args());
+        args
+    }
+
+    /// How to reach the host, shared by commands and the master connection.
+    fn connection_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(port) = self.port {
+            args.extend([String::from("-p"), port.to_string()]);
+        }
+        if let Some(identity_file) = &self.identity_file {
+            args.extend([String::from("-i"), identity_file.clone()]);
+        }
+        if let Some(jump_host) = &self.jump_host {
+            args.extend([String::from("-J"), jump_host.clone()]);
+        }
+        for option in &self.options {
+            args.extend([String::from("-o"), option.clone()]);
+        }
+        args.push(String::from("--"));
+        args.push(format!("{}@{}", self.username, self.hostname));
+        args
+    }
+}
+
+impl Executor for Ssh {
+    fn compose(&self, args: &[String]) -> Job {
+        if let Some(control_path) = &self.control_path {
+            multiplex::ensure_master(control_path, &self.connection_args());
+        }
+
+        let args = [self.compose_args.as_slice(), args].concat();
+        let mut command = Command::new("ssh");
+        command
+            .args(self.batch_args())
+            .arg(command::stoppable_script(&self.path, &args));
+
+        // Stdin stays open for as long as the job, see `stoppable_script`.
+        stream(command, Stdio::piped())
+    }
+
+    fn interactive(&self, args: &[String]) -> io::Result<Option<i32>> {
+        let args = [self.compose_args.as_slice(), args].concat();
+        let status = Command::new("ssh")
+            .arg("-tt")
+            .args(self.args())
+            .arg(command::remote_script(&self.path, &args))
+            .status()?;
+
+        Ok(status.code())
+    }
+}
+
+/// Runs compose on this machine.
+pub struct Local {
+    pub path: String,
+    /// Compose options given before every subcommand.
+    pub compose_args: Vec<String>,
+}
+
+impl Local {
+    /// The project directory, with a leading `~/` expanded as a shell would.
+    fn dir(&self) -> PathBuf {
+        let home = dirs::home_dir();
+        match (self.path.strip_prefix("~/"), home) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => PathBuf::from(&self.path),
+        }
+    }
+}
+
+impl Executor for Local {
+    fn compose(&self, args: &[String]) -> Job {
+        let mut command = Command::new("docker");
+        command
+            .current_dir(self.dir())
+            .arg("compose")
+            .args(&self.compose_args)
+            .args(args);
+
+        stream(command, Stdio::null())
+    }
+
+    fn interactive(&self, args: &[String]) -> io::Result<Option<i32>> {
+        let status = Command::new("docker")
+            .current_dir(self.dir())
+            .arg("compose")
+            .args(&self.compose_args)
+            .args(args)
+            .status()?;
+
+        Ok(status.code())
+    }
+}
+
+/// Records every invocation instead of running it and replies with canned
+/// output. Used to preview commands before they run, and in tests.
+#[derive(Default)]
+pub struct Recording {
+    pub calls: std::cell::RefCell<Vec<Vec<String>>>,
+    pub lines: Vec<String>,
+    pub code: Option<i32>,
+}
+
+impl Executor for Recording {
+    fn compose(&self, args: &[String]) -> Job {
+        self.calls.borrow_mut().push(args.to_vec());
+
+        let (tx, rx) = mpsc::channel();
+        for line in &self.lines {
+            tx.send(Output::Stdout(line.to_owned())).unwrap();
+        }
+        tx.send(Output::Exit(CommandResult {
+            code: self.code,
+            duration: Duration::ZERO,
+            output: self.lines.clone(),
+        }))
+        .unwrap();
+        Job {
+            receiver: rx,
+            child: None,
+        }
+    }
+
+    fn interactive(&self, args: &[String]) -> io::Result<Option<i32>> {
+        self.calls.borrow_mut().push(args.to_vec());
+        Ok(self.code)
+    }
+}
+
+static BATCH_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Makes ssh fail rather than prompt for passwords, passphrases or unknown
+/// host keys in commands run from now on, for while the TUI owns the
+/// terminal and a prompt would draw over it. The error then lands in the
+/// command's output.
+pub fn set_batch_mode() {
+    BATCH_MODE.store(true, Ordering::Relaxed);
+}
+
+/// Picks the executor named by the profile's `executor` key.
+pub fn from_profile(profile: &UserProfile) -> Box<dyn Executor> {
+    let config = &profile.config;
+    match config.executor {
+        env::Executor::Local => Box::new(Local {
+            path: config.path.clone(),
+            compose_args: command::global_args(config),
+        }),
+        env::Executor::Ssh => Box::new(Ssh {
+            username: config.username.clone(),
+            hostname: config.hostname.clone(),
+            path: config.path.clone(),
+            port: config.port,
+            identity_file: config.identity_file.clone(),
+            jump_host: config.jump_host.clone(),
+            options: config.ssh_options.clone(),
+            control_path: multiplex::control_path(&profile.profile),
+            compose_args: command::global_args(config),
+            batch_mode: BATCH_MODE.load(Ordering::Relaxed),
+        }),
+    }
+}
+
+/// Spawns `command` and forwards its stdout/stderr line by line, followed by
+/// its [`CommandResult`], so the TUI can render the output while it is running.
+fn stream(mut command: Command, stdin: Stdio) -> Job {
+    let (tx, rx) = mpsc::channel();
+    let started = Instant::now();
+
+    let mut child = match command
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            let line = format!(
+                "failed to start {}: {}",
+                command.get_program().to_string_lossy(),
+                e
+            );
+            tx.send(Output::Stderr(line.clone())).unwrap_or(());
+            tx.send(Output::Exit(CommandResult {
+                code: None,
+                duration: started.elapsed(),
+                output: vec![line],
+            }))
+            .unwrap_or(());
+            wake();
+            return Job {
+                receiver: rx,
+                child: None,
+            };
+        }
+    };
+
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+    let child = Arc::new(Mutex::new(child));
+    let waited = child.clone();
+    let captured = Arc::new(Mutex::new(Vec::new()));
+
+    let stderr_tx = tx.clone();
+    let stderr_captured = captured.clone();
+    let stderr_reader =
+        thread::spawn(move || forward(stderr, stderr_tx, &stderr_captured, Output::Stderr));
+
+    thread::spawn(move || {
+        forward(stdout, tx.clone(), &captured, Output::Stdout);
+        stderr_reader.join().unwrap_or(());
+
+        // Poll rather than block in `wait` so `Job::drop` can take the lock
+        // to kill the child.
+        let code = loop {
+            match waited.lock().map(|mut c| c.try_wait()) {
+                Ok(Ok(Some(status))) => break status.code(),
+                Ok(Ok(None)) => (),
+                _ => break None,
+            }
+            thread::sleep(Duration::from_millis(50));
+        };
+        let output = captured.lock().map(|c| c.clone()).unwrap_or_default();
+        tx.send(Output::Exit(CommandResult {
+            code,
+            duration: started.elapsed(),
+            output,
+        }))
+        .unwrap_or(());
+        wake();
+    });
+
+    Job {
+        receiver: rx,
+        child: Some(child),
+    }
+}
+
+fn forward<R: Read>(
+    reader: R,
+    tx: Sender<Output>,
+    captured: &Mutex<Vec<String>>,
+    wrap: fn(String) -> Output,
+) {
+    for line in BufReader::new(reader).lines().map_while(Result::ok) {
+        let line = line.trim_end_matches('\r').to_owned();
+        if let Ok(mut captured) = captured.lock() {
+            captured.push(line.clone());
+        }
+        if tx.send(wrap(line)).is_err() {
+            break;
+        }
+        wake();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ssh_destination_comes_last() {
+        let ssh = Ssh {
+            username: String::from("deploy"),
+            hostname: String::from("example.com"),
+            ..Ssh::default()
+        };
+        assert_eq!(ssh.args(), vec!["--", "deploy@example.com"]);
+    }
+
+    #[test]
+    fn ssh_connection_options_are_passed() {
+        let ssh = Ssh {
+            username: String::from("deploy"),
+            hostname: String::from("10.0.0.5"),
+            port: Some(2222),
+            identity_file: Some(String::from("~/.ssh/deploy")),
+            jump_host: Some(String::from("ops@bastion")),
+            options: vec![String::from("ServerAliveInterval=30")],
+            ..Ssh::default()
+        };
+        assert_eq!(
+            ssh.args().join(" "),
+            "-p 2222 -i ~/.ssh/deploy -J ops@bastion -o ServerAliveInterval=30 -- deploy@10.0.0.5"
+        );
+    }
+
+    #[test]
+    fn ssh_reuses_the_master_connection() {
+        let ssh = Ssh {
+            username: String::from("deploy"),
+            hostname: String::from("example.com"),
+            port: Some(2222),
+            control_path: Some(PathBuf::from("/tmp/compose-tui-1/prod")),
+            ..Ssh::default()
+        };
+        assert_eq!(
+            ssh.args().join(" "),
+            "-o ControlMaster=no -o ControlPath=/tmp/compose-tui-1/prod -p 2222 -- deploy@example.com"
+        );
+    }
+
+    #[test]
+    fn ssh_only_stops_prompting_in_batch_mode() {
+        let mut ssh = Ssh {
+            username: String::from("deploy"),
+            hostname: String::from("example.com"),
+            ..Ssh::default()
+        };
+        assert_eq!(ssh.batch_args().join(" "), "-- deploy@example.com");
+        ssh.batch_mode = true;
+        assert_eq!(
+            ssh.batch_args().join(" "),
+            "-o BatchMode=yes -- deploy@example.com"
+        );
+    }
+
+    #[test]
+    fn local_paths_expand_the_home_directory() {
+        let local = |path: &str| Local {
+            path: String::from(path),
+            compose_args: Vec::new(),
+        };
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(local("~/stack").dir(), home.join("stack"));
+        assert_eq!(local("/srv/~/stack").dir(), PathBuf::from("/srv/~/stack"));
+    }
+}
@@ -1,85 +1,460 @@
+pub mod command;
+pub mod executor;
+pub mod multiplex;
+pub mod stats;
+pub mod status;
+
+use serde::{Deserialize, Serialize};
 use std::{
-    io::{self, Write},
-    process::Command,
+    io,
+    process::Child,
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Arc, Mutex, OnceLock,
+    },
+    time::Duration,
 };
 
-pub fn up(username: String, hostname: String, path: String, target: Option<String>) {
-    let output = Command::new("ssh")
-        .arg("-tt")
-        .arg(format!("{}@{}", username, hostname))
-        .arg(format!("cd {}", path))
-        .arg("&& docker compose up")
-        .arg("-d")
-        .arg(match target {
-            Some(_target) => {
-                format!("--build {}", _target)
-            }
-            None => "".to_owned(),
-        })
-        .spawn()
-        .expect("command failed to start");
-
-    let output = output.wait_with_output().unwrap();
-
-    io::stdout().write_all(&output.stdout).unwrap();
-    io::stderr().write_all(&output.stderr).unwrap();
-}
-
-pub fn down(username: String, hostname: String, path: String, rmi: Option<String>) {
-    let output = Command::new("ssh")
-        .arg("-tt")
-        .arg(format!("{}@{}", username, hostname))
-        .arg(format!("cd {}", path))
-        .arg("&& docker compose down")
-        .arg(match rmi {
-            Some(r) => format!("--rmi {}", r),
-            None => String::from(""),
-        })
-        .spawn()
-        .expect("command failed to start");
-
-    let output = output.wait_with_output().unwrap();
-
-    io::stdout().write_all(&output.stdout).unwrap();
-    io::stderr().write_all(&output.stderr).unwrap();
-}
-
-pub fn start(username: String, hostname: String, path: String, target: Option<String>) {
-    let output = Command::new("ssh")
-        .arg("-tt")
-        .arg(format!("{}@{}", username, hostname))
-        .arg(format!("cd {} && docker compose start", path))
-        .arg(match target {
-            Some(_target) => {
-                format!("{}", _target)
-            }
-            None => "".to_owned(),
-        })
-        .spawn()
-        .expect("command failed to start");
+pub use command::ComposeCommand;
+pub use executor::Executor;
+
+#[derive(Debug)]
+pub enum Output {
+    Stdout(String),
+    Stderr(String),
+    Exit(CommandResult),
+}
+
+static WAKER: OnceLock<Box<dyn Fn() + Send + Sync>> = OnceLock::new();
+
+/// Registers a function to call whenever a job has new output, so the UI can
+/// redraw right away instead of on its next tick.
+pub fn set_waker(waker: impl Fn() + Send + Sync + 'static) {
+    let _ = WAKER.set(Box::new(waker));
+}
+
+fn wake() {
+    if let Some(waker) = WAKER.get() {
+        waker();
+    }
+}
 
-    let output = output.wait_with_output().unwrap();
+/// A running compose command. Dropping a job kills the command if it has not
+/// finished yet, which is how followed logs and stale refreshes are stopped.
+pub struct Job {
+    receiver: Receiver<Output>,
+    child: Option<Arc<Mutex<Child>>>,
+}
+
+impl Job {
+    pub fn try_recv(&self) -> Result<Output, TryRecvError> {
+        self.receiver.try_recv()
+    }
 
-    io::stdout().write_all(&output.stdout).unwrap();
-    io::stderr().write_all(&output.stderr).unwrap();
+    pub fn iter(&self) -> mpsc::Iter<'_, Output> {
+        self.receiver.iter()
+    }
 }
 
-pub fn stop(username: String, hostname: String, path: String, target: Option<String>) {
-    let output = Command::new("ssh")
-        .arg("-tt")
-        .arg(format!("{}@{}", username, hostname))
-        .arg(format!("cd {} && docker compose stop", path))
-        .arg(match target {
-            Some(_target) => {
-                format!("{}", _target)
+impl Drop for Job {
+    fn drop(&mut self) {
+        if let Some(child) = &self.child {
+            if let Ok(mut child) = child.lock() {
+                child.kill().unwrap_or(());
             }
-            None => "".to_owned(),
-        })
-        .spawn()
-        .expect("command failed to start");
+        }
+    }
+}
+
+impl std::fmt::Debug for Job {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Job").finish_non_exhaustive()
+    }
+}
+
+/// Outcome of a finished compose command. `code` is `None` when the command
+/// could not be started or was killed by a signal.
+#[derive(Debug, Clone)]
+pub struct CommandResult {
+    pub code: Option<i32>,
+    pub duration: Duration,
+    pub output: Vec<String>,
+}
+
+impl CommandResult {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+
+    /// Exit code to hand back to the shell for this result.
+    pub fn exit_code(&self) -> i32 {
+        self.code.unwrap_or(1)
+    }
+}
+
+/// Flags for `docker compose up`, remembered per profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpOptions {
+    pub detach: bool,
+    /// `Some(true)` for `--build`, `Some(false)` for `--no-build`. `None`
+    /// is not written, so a missing key has to read back as `None` rather
+    /// than as the default.
+    #[serde(default)]
+    pub build: Option<bool>,
+    pub force_recreate: bool,
+    pub no_deps: bool,
+    /// `always`, `missing` or `never`.
+    pub pull: Option<String>,
+    pub wait: bool,
+    /// `service=replicas` pairs, each passed as `--scale`.
+    pub scale: Vec<String>,
+}
+
+impl Default for UpOptions {
+    fn default() -> UpOptions {
+        UpOptions {
+            detach: true,
+            build: Some(true),
+            force_recreate: false,
+            no_deps: false,
+            pull: None,
+            wait: false,
+            scale: Vec::new(),
+        }
+    }
+}
+
+impl UpOptions {
+    pub fn is_default(&self) -> bool {
+        self == &UpOptions::default()
+    }
+}
+
+pub fn up(executor: &dyn Executor, target: Option<String>, options: &UpOptions) -> Job {
+    let mut command = ComposeCommand::new("up");
+    if options.detach {
+        command = command.arg("-d");
+    }
+    match options.build {
+        Some(true) => command = command.arg("--build"),
+        Some(false) => command = command.arg("--no-build"),
+        None => (),
+    }
+    if options.force_recreate {
+        command = command.arg("--force-recreate");
+    }
+    if options.no_deps {
+        command = command.arg("--no-deps");
+    }
+    if let Some(pull) = &options.pull {
+        command = command.arg("--pull").arg(pull);
+    }
+    if options.wait {
+        command = command.arg("--wait");
+    }
+    for scale in &options.scale {
+        command = command.arg("--scale").arg(scale);
+    }
+    if let Some(target) = target {
+        command = command.services(&target);
+    }
+
+    executor.compose(command.args())
+}
+
+/// Flags for `docker compose down`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DownOptions {
+    /// `local` or `all`.
+    pub rmi: Option<String>,
+    pub volumes: bool,
+    pub remove_orphans: bool,
+    /// Seconds to wait for containers to stop.
+    pub timeout: Option<u32>,
+}
+
+pub fn down(executor: &dyn Executor, options: &DownOptions) -> Job {
+    let mut command = ComposeCommand::new("down");
+    if let Some(rmi) = &options.rmi {
+        command = command.arg("--rmi").arg(rmi);
+    }
+    if options.volumes {
+        command = command.arg("--volumes");
+    }
+    if options.remove_orphans {
+        command = command.arg("--remove-orphans");
+    }
+    if let Some(timeout) = options.timeout {
+        command = command.arg("--timeout").arg(&timeout.to_string());
+    }
+
+    executor.compose(command.args())
+}
+
+pub fn start(executor: &dyn Executor, target: Option<String>) -> Job {
+    let mut command = ComposeCommand::new("start");
+    if let Some(target) = target {
+        command = command.services(&target);
+    }
+
+    executor.compose(command.args())
+}
+
+pub fn stop(executor: &dyn Executor, target: Option<String>) -> Job {
+    let mut command = ComposeCommand::new("stop");
+    if let Some(target) = target {
+        command = command.services(&target);
+    }
+
+    executor.compose(command.args())
+}
 
-    let output = output.wait_with_output().unwrap();
+pub fn restart(executor: &dyn Executor, target: Option<String>) -> Job {
+    let mut command = ComposeCommand::new("restart");
+    if let Some(target) = target {
+        command = command.services(&target);
+    }
 
-    io::stdout().write_all(&output.stdout).unwrap();
-    io::stderr().write_all(&output.stderr).unwrap();
+    executor.compose(command.args())
+}
+
+pub fn pull(executor: &dyn Executor, target: Option<String>) -> Job {
+    let mut command = ComposeCommand::new("pull");
+    if let Some(target) = target {
+        command = command.services(&target);
+    }
+
+    executor.compose(command.args())
+}
+
+pub fn build(executor: &dyn Executor, target: Option<String>, no_cache: bool) -> Job {
+    let mut command = ComposeCommand::new("build");
+    if no_cache {
+        command = command.arg("--no-cache");
+    }
+    if let Some(target) = target {
+        command = command.services(&target);
+    }
+
+    executor.compose(command.args())
+}
+
+pub fn ps(executor: &dyn Executor, target: Option<String>) -> Job {
+    let mut command = ComposeCommand::new("ps");
+    if let Some(target) = target {
+        command = command.services(&target);
+    }
+
+    executor.compose(command.args())
+}
+
+pub fn logs(executor: &dyn Executor, target: Option<String>, tail: usize) -> Job {
+    let mut command = ComposeCommand::new("logs")
+        .arg("--follow")
+        .arg("--no-color")
+        .arg("--tail")
+        .arg(&tail.to_string());
+    if let Some(target) = target {
+        command = command.services(&target);
+    }
+
+    executor.compose(command.args())
+}
+
+pub fn ps_json(executor: &dyn Executor) -> Job {
+    let command = ComposeCommand::new("ps")
+        .arg("--all")
+        .arg("--format")
+        .arg("json");
+
+    executor.compose(command.args())
+}
+
+/// Takes one snapshot of the resource usage of the project's containers.
+pub fn stats_json(executor: &dyn Executor) -> Job {
+    let command = ComposeCommand::new("stats")
+        .arg("--no-stream")
+        .arg("--format")
+        .arg("json");
+
+    executor.compose(command.args())
+}
+
+/// Tries bash and falls back to sh when a service has no shell configured.
+static DEFAULT_SHELL: &str = "if command -v bash >/dev/null 2>&1; then exec bash; else exec sh; fi";
+
+/// Opens an interactive `shell` in the running container of `service`.
+pub fn exec(
+    executor: &dyn Executor,
+    service: &str,
+    shell: Option<&str>,
+) -> io::Result<Option<i32>> {
+    let command = ComposeCommand::new("exec").arg("-it").arg(service);
+    // The configured shell is a command line split on whitespace, so it
+    // can carry arguments without the container needing `sh`.
+    let command = match shell.filter(|s| !s.trim().is_empty()) {
+        Some(shell) => shell.split_whitespace().fold(command, ComposeCommand::arg),
+        None => command.arg("sh").arg("-c").arg(DEFAULT_SHELL),
+    };
+
+    executor.interactive(command.args())
+}
+
+/// Lists the services defined by the compose file, one per line.
+pub fn config_services(executor: &dyn Executor) -> Job {
+    let command = ComposeCommand::new("config").arg("--services");
+
+    executor.compose(command.args())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use executor::Recording;
+
+    #[test]
+    fn up_builds_requested_services() {
+        let executor = Recording::default();
+        up(
+            &executor,
+            Some(String::from("web worker")),
+            &UpOptions::default(),
+        );
+        assert_eq!(
+            executor.calls.borrow()[0],
+            vec!["up", "-d", "--build", "web", "worker"]
+        );
+    }
+
+    #[test]
+    fn up_options_map_to_flags() {
+        let executor = Recording::default();
+        let options = UpOptions {
+            detach: false,
+            build: Some(false),
+            force_recreate: true,
+            no_deps: true,
+            pull: Some(String::from("always")),
+            wait: true,
+            scale: vec![String::from("worker=3")],
+        };
+        up(&executor, Some(String::from("worker")), &options);
+        assert_eq!(
+            executor.calls.borrow()[0].join(" "),
+            "up --no-build --force-recreate --no-deps --pull always --wait --scale worker=3 worker"
+        );
+    }
+
+    #[test]
+    fn down_passes_options() {
+        let executor = Recording::default();
+        down(
+            &executor,
+            &DownOptions {
+                rmi: Some(String::from("local")),
+                volumes: true,
+                remove_orphans: true,
+                timeout: Some(30),
+            },
+        );
+        down(&executor, &DownOptions::default());
+        assert_eq!(
+            *executor.calls.borrow(),
+            vec![
+                vec![
+                    "down",
+                    "--rmi",
+                    "local",
+                    "--volumes",
+                    "--remove-orphans",
+                    "--timeout",
+                    "30"
+                ],
+                vec!["down"]
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_target_means_all_services() {
+        let executor = Recording::default();
+        start(&executor, Some(String::new()));
+        stop(&executor, None);
+        assert_eq!(*executor.calls.borrow(), vec![vec!["start"], vec!["stop"]]);
+    }
+
+    #[test]
+    fn build_can_skip_cache() {
+        let executor = Recording::default();
+        build(&executor, Some(String::from("web")), true);
+        build(&executor, None, false);
+        assert_eq!(
+            *executor.calls.borrow(),
+            vec![vec!["build", "--no-cache", "web"], vec!["build"]]
+        );
+    }
+
+    #[test]
+    fn exec_uses_the_configured_shell() {
+        let executor = Recording::default();
+        exec(&executor, "web", Some("bash")).unwrap();
+        exec(&executor, "db", None).unwrap();
+        let calls = executor.calls.borrow();
+        assert_eq!(calls[0], vec!["exec", "-it", "web", "bash"]);
+        assert_eq!(calls[1][..5], ["exec", "-it", "db", "sh", "-c"]);
+    }
+
+    #[test]
+    fn exec_passes_the_shell_arguments_separately() {
+        let executor = Recording::default();
+        exec(&executor, "db", Some("psql  -U postgres")).unwrap();
+        assert_eq!(
+            executor.calls.borrow()[0],
+            vec!["exec", "-it", "db", "psql", "-U", "postgres"]
+        );
+    }
+
+    #[test]
+    fn logs_follow_selected_services() {
+        let executor = Recording::default();
+        logs(&executor, Some(String::from("web")), 50);
+        assert_eq!(
+            executor.calls.borrow()[0],
+            vec!["logs", "--follow", "--no-color", "--tail", "50", "web"]
+        );
+    }
+
+    #[test]
+    fn output_is_forwarded_with_exit_code() {
+        let executor = Recording {
+            lines: vec![String::from("Container web  Started")],
+            code: Some(0),
+            ..Recording::default()
+        };
+        let output: Vec<Output> = start(&executor, None).iter().collect();
+        assert!(matches!(&output[0], Output::Stdout(line) if line == "Container web  Started"));
+        match &output[1] {
+            Output::Exit(result) => {
+                assert!(result.success());
+                assert_eq!(result.output, vec!["Container web  Started"]);
+            }
+            o => panic!("unexpected {:?}", o),
+        }
+    }
+
+    #[test]
+    fn failure_keeps_exit_code() {
+        let executor = Recording {
+            code: Some(3),
+            ..Recording::default()
+        };
+        match stop(&executor, None).iter().last() {
+            Some(Output::Exit(result)) => {
+                assert!(!result.success());
+                assert_eq!(result.exit_code(), 3);
+            }
+            o => panic!("unexpected {:?}", o),
+        }
+    }
 }
@@ -0,0 +1,150 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs::{self, DirBuilder},
+    hash::{Hash, Hasher},
+    os::unix::fs::DirBuilderExt,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Control sockets of the ssh master connections opened by this process,
+/// and when each master was last started.
+#[derive(Debug)]
+struct Session {
+    dir: PathBuf,
+    masters: Mutex<HashMap<PathBuf, Instant>>,
+}
+
+/// How long a new master has to connect before it is checked on.
+static MASTER_CONNECT: Duration = Duration::from_secs(15);
+
+static SESSION: OnceLock<Session> = OnceLock::new();
+
+/// Turns on connection sharing for the rest of the process. Until this is
+/// called, and if the socket directory cannot be created, every ssh command
+/// opens its own connection.
+///
+/// The directory is private to this user and must not exist yet, so no one
+/// else can plant a socket in it. Its path is kept short since ssh appends
+/// a suffix to the socket name and unix sockets are limited to ~104 bytes.
+pub fn start() {
+    let base = match dirs::runtime_dir() {
+        Some(dir) => dir,
+        None => match dirs::home_dir() {
+            Some(home) => home.join(".ssh"),
+            None => return,
+        },
+    };
+    let dir = base.join(format!("ct-{}", std::process::id()));
+    if DirBuilder::new().mode(0o700).create(&dir).is_ok() {
+        let _ = SESSION.set(Session {
+            dir,
+            masters: Mutex::new(HashMap::new()),
+        });
+    }
+}
+
+/// Where the master connection for `profile` listens, if sharing is on.
+pub fn control_path(profile: &str) -> Option<PathBuf> {
+    let session = SESSION.get()?;
+    Some(session.dir.join(socket_name(profile)))
+}
+
+/// A fixed-length name for the socket, however long the profile name is.
+fn socket_name(profile: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    profile.hash(&mut hasher);
+    format!("{:08x}", hasher.finish() as u32)
+}
+
+/// Opens a background master on `control_path` unless one is already up.
+/// Commands do not wait for it: until the socket exists they connect on
+/// their own, which ssh does whenever the control path cannot be opened.
+/// The master exits a minute after its last command, so one left behind by
+/// a crash or a closed terminal does not stay open; the next command after
+/// that, or after a master failed to connect, starts a new one.
+pub fn ensure_master(control_path: &Path, args: &[String]) {
+    let session = match SESSION.get() {
+        Some(session) => session,
+        None => return,
+    };
+    let mut masters = match session.masters.lock() {
+        Ok(masters) => masters,
+        Err(_) => return,
+    };
+    if let Some(started) = masters.get(control_path) {
+        if started.elapsed() < MASTER_CONNECT || control(control_path, "check") {
+            return;
+        }
+        // A master killed outright leaves its socket behind, which would
+        // keep the new one from listening.
+        let _ = fs::remove_file(control_path);
+    }
+    masters.insert(control_path.to_owned(), Instant::now());
+
+    // Null stdio so the master, which outlives this command, never holds on
+    // to a pipe that a job is reading until EOF.
+    let child = Command::new("ssh")
+        .args([
+            "-M",
+            "-N",
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "ControlPersist=60",
+            "-o",
+        ])
+        .arg(format!("ControlPath={}", control_path.display()))
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = child {
+        thread::spawn(move || child.wait());
+    }
+}
+
+/// Closes every master connection and removes the socket directory.
+pub fn end() {
+    let session = match SESSION.get() {
+        Some(session) => session,
+        None => return,
+    };
+    if let Ok(masters) = session.masters.lock() {
+        for control_path in masters.keys() {
+            control(control_path, "exit");
+        }
+    }
+    let _ = fs::remove_dir_all(&session.dir);
+}
+
+/// Sends `command` (`check` or `exit`) to the master on `control_path` and
+/// tells whether it answered.
+fn control(control_path: &Path, command: &str) -> bool {
+    Command::new("ssh")
+        .arg("-o")
+        .arg(format!("ControlPath={}", control_path.display()))
+        .args(["-O", command, "compose-tui"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_names_are_short_and_stable() {
+        let long = "a-very-long-profile-name-for-the-production-cluster-in-europe";
+        assert_eq!(socket_name(long).len(), 8);
+        assert_eq!(socket_name(long), socket_name(long));
+        assert_ne!(socket_name("prod"), socket_name("staging"));
+    }
+}
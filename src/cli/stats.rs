@@ -0,0 +1,156 @@
+use serde::Deserialize;
+
+/// One container row from `docker compose stats --format json`. Every value
+/// is preformatted by docker, such as `12.5%` or `1.2MiB / 2GiB`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Row {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "CPUPerc")]
+    pub cpu: String,
+    #[serde(rename = "MemUsage")]
+    pub memory: String,
+    #[serde(rename = "MemPerc")]
+    pub memory_percent: String,
+    #[serde(rename = "NetIO")]
+    pub network: String,
+    #[serde(rename = "BlockIO")]
+    pub block: String,
+}
+
+/// A container's usage at one point in time, in percent and bytes. Network
+/// and block I/O are totals since the container started.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Sample {
+    pub name: String,
+    pub cpu: f64,
+    pub memory: f64,
+    pub memory_limit: f64,
+    pub memory_percent: f64,
+    pub net_rx: f64,
+    pub net_tx: f64,
+    pub block_read: f64,
+    pub block_write: f64,
+}
+
+impl From<Row> for Sample {
+    fn from(row: Row) -> Sample {
+        let (memory, memory_limit) = pair(&row.memory);
+        let (net_rx, net_tx) = pair(&row.network);
+        let (block_read, block_write) = pair(&row.block);
+        Sample {
+            name: row.name,
+            cpu: percent(&row.cpu),
+            memory,
+            memory_limit,
+            memory_percent: percent(&row.memory_percent),
+            net_rx,
+            net_tx,
+            block_read,
+            block_write,
+        }
+    }
+}
+
+/// Parses the output of one `stats --no-stream` run, skipping lines that
+/// are not JSON objects.
+pub fn parse(lines: &[String]) -> Vec<Sample> {
+    let mut samples: Vec<Sample> = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| serde_json::from_str::<Row>(line).ok())
+        .map(Sample::from)
+        .collect();
+    samples.sort_by(|a, b| a.name.cmp(&b.name));
+    samples
+}
+
+fn percent(value: &str) -> f64 {
+    value.trim().trim_end_matches('%').parse().unwrap_or(0.0)
+}
+
+/// Splits `used / total` into bytes.
+fn pair(value: &str) -> (f64, f64) {
+    match value.split_once('/') {
+        Some((a, b)) => (bytes(a), bytes(b)),
+        None => (bytes(value), 0.0),
+    }
+}
+
+/// Reads sizes the way docker prints them: `0B`, `1.5kB`, `12MiB`, `2GB`.
+fn bytes(value: &str) -> f64 {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = match number.parse() {
+        Ok(number) => number,
+        Err(_) => return 0.0,
+    };
+    let scale = match unit.trim().to_ascii_lowercase().as_str() {
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "tb" => 1e12,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => 1.0,
+    };
+    number * scale
+}
+
+/// Formats bytes the way docker does, with decimal units.
+pub fn format_bytes(value: f64) -> String {
+    let units = ["B", "kB", "MB", "GB", "TB"];
+    let mut value = value;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < units.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{:.0}{}", value, units[unit]),
+        _ => format!("{:.1}{}", value, units[unit]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stats_rows() {
+        let lines = vec![
+            String::from("Warning: Permanently added 'host' to the list of known hosts."),
+            String::from(
+                r#"{"BlockIO":"4MB / 0B","CPUPerc":"0.52%","Container":"9c1f","ID":"9c1f","MemPerc":"3.10%","MemUsage":"62.5MiB / 1.944GiB","Name":"app-web-1","NetIO":"1.2kB / 648B","PIDs":"5"}"#,
+            ),
+            String::from(
+                r#"{"BlockIO":"0B / 0B","CPUPerc":"101.00%","MemPerc":"0.00%","MemUsage":"0B / 0B","Name":"app-db-1","NetIO":"0B / 0B"}"#,
+            ),
+        ];
+        let samples = parse(&lines);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].name, "app-db-1");
+        assert_eq!(samples[0].cpu, 101.0);
+
+        let web = &samples[1];
+        assert_eq!(web.cpu, 0.52);
+        assert_eq!(web.memory, 62.5 * 1024.0 * 1024.0);
+        assert_eq!(web.memory_percent, 3.1);
+        assert_eq!((web.net_rx, web.net_tx), (1200.0, 648.0));
+        assert_eq!((web.block_read, web.block_write), (4e6, 0.0));
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(648.0), "648B");
+        assert_eq!(format_bytes(1200.0), "1.2kB");
+        assert_eq!(format_bytes(2.5e9), "2.5GB");
+    }
+}
@@ -0,0 +1,115 @@
+use serde::Deserialize;
+
+/// One container row from `docker compose ps --format json`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Service {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Service")]
+    pub service: String,
+    #[serde(rename = "State")]
+    pub state: String,
+    #[serde(rename = "Health")]
+    pub health: String,
+    #[serde(rename = "Status")]
+    pub status: String,
+    #[serde(rename = "Ports")]
+    pub ports: String,
+    #[serde(rename = "Publishers")]
+    pub publishers: Option<Vec<Publisher>>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Publisher {
+    #[serde(rename = "URL")]
+    pub url: String,
+    #[serde(rename = "TargetPort")]
+    pub target_port: u16,
+    #[serde(rename = "PublishedPort")]
+    pub published_port: u16,
+    #[serde(rename = "Protocol")]
+    pub protocol: String,
+}
+
+impl Service {
+    /// Published ports as `host:port->target/proto`, falling back to the
+    /// preformatted `Ports` column printed by newer compose releases.
+    pub fn ports(&self) -> String {
+        let publishers = match &self.publishers {
+            Some(publishers) => publishers,
+            None => return self.ports.clone(),
+        };
+        let ports: Vec<String> = publishers
+            .iter()
+            .filter(|p| p.published_port != 0)
+            .map(|p| {
+                format!(
+                    "{}:{}->{}/{}",
+                    p.url, p.published_port, p.target_port, p.protocol
+                )
+            })
+            .collect();
+        match ports.is_empty() {
+            true => self.ports.clone(),
+            false => ports.join(", "),
+        }
+    }
+}
+
+/// Parses `ps --format json` output. Older compose releases print a single
+/// JSON array, newer ones one object per line; lines that are neither (such
+/// as ssh banners on stderr) are skipped.
+pub fn parse(lines: &[String]) -> Vec<Service> {
+    let mut services = Vec::new();
+    for line in lines {
+        let line = line.trim();
+        if line.starts_with('[') {
+            if let Ok(mut list) = serde_json::from_str::<Vec<Service>>(line) {
+                services.append(&mut list);
+            }
+        } else if line.starts_with('{') {
+            if let Ok(service) = serde_json::from_str::<Service>(line) {
+                services.push(service);
+            }
+        }
+    }
+    services.sort_by(|a, b| a.service.cmp(&b.service).then(a.name.cmp(&b.name)));
+    services
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_array() {
+        let lines = vec![String::from(
+            r#"[{"Name":"app-web-1","Service":"web","State":"running","Health":"healthy","Status":"Up 2 hours (healthy)","Publishers":[{"URL":"0.0.0.0","TargetPort":80,"PublishedPort":8080,"Protocol":"tcp"}]}]"#,
+        )];
+        let services = parse(&lines);
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].service, "web");
+        assert_eq!(services[0].health, "healthy");
+        assert_eq!(services[0].ports(), "0.0.0.0:8080->80/tcp");
+    }
+
+    #[test]
+    fn parses_one_object_per_line() {
+        let lines = vec![
+            String::from("Warning: Permanently added 'host' to the list of known hosts."),
+            String::from(
+                r#"{"Name":"app-worker-1","Service":"worker","State":"exited","Status":"Exited (1) 3 minutes ago","Ports":""}"#,
+            ),
+            String::from(
+                r#"{"Name":"app-db-1","Service":"db","State":"running","Status":"Up 5 days","Ports":"5432/tcp"}"#,
+            ),
+        ];
+        let services = parse(&lines);
+        let names: Vec<&str> = services.iter().map(|s| s.service.as_str()).collect();
+        assert_eq!(names, vec!["db", "worker"]);
+        assert_eq!(services[0].ports(), "5432/tcp");
+        assert_eq!(services[1].state, "exited");
+    }
+}
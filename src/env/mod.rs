@@ -1,80 +1,437 @@
+pub mod ssh_config;
+
 use dirs::home_dir;
-use dotenv;
-use std::{env, fs, io::Write};
 
-pub fn load_name() -> Vec<String> {
-    let target_path = home_dir()
-        .and_then(|a| Some(a.join("pcode-cli/docker")))
-        .unwrap();
+use crate::cli::UpOptions;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A saved connection profile, stored as `~/pcode-cli/docker/<name>.toml`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub username: String,
+    pub hostname: String,
+    pub path: String,
+    pub executor: Executor,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jump_host: Option<String>,
+    /// Extra `ssh -o` options such as `ServerAliveInterval=30`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ssh_options: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub compose_files: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_name: Option<String>,
+    /// Compose profiles to enable, each passed as `--profile`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub compose_profiles: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env_files: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub default_services: Vec<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    /// Changes to a protected profile must be confirmed by typing its name.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub protected: bool,
+    /// Shell to run for `exec`, by service.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub shells: BTreeMap<String, String>,
+    /// Options last used for `up`.
+    #[serde(skip_serializing_if = "UpOptions::is_default")]
+    pub up: UpOptions,
+}
+
+/// Where `docker compose` runs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Executor {
+    /// On `hostname`, over ssh. Older profiles left the key empty.
+    #[default]
+    #[serde(alias = "")]
+    Ssh,
+    /// On this machine.
+    Local,
+}
+
+impl fmt::Display for Executor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Executor::Ssh => write!(f, "ssh"),
+            Executor::Local => write!(f, "local"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The home directory, where profiles live, could not be determined.
+    NoHome,
+    /// Reading or writing a profile file failed.
+    Io { path: PathBuf, source: io::Error },
+    /// A profile file is not valid TOML for [`Profile`].
+    Parse {
+        profile: String,
+        source: toml::de::Error,
+    },
+    /// A profile could not be written as TOML.
+    Serialize {
+        profile: String,
+        source: toml::ser::Error,
+    },
+    /// A key the profile needs is absent or empty.
+    MissingKey { profile: String, key: &'static str },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoHome => write!(f, "could not find the home directory"),
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Parse { profile, source } => {
+                write!(f, "profile `{}` is invalid: {}", profile, source.message())
+            }
+            Error::Serialize { profile, source } => {
+                write!(f, "profile `{}` could not be saved: {}", profile, source)
+            }
+            Error::MissingKey { profile, key } => {
+                write!(f, "profile `{}` is missing `{}`", profile, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse { source, .. } => Some(source),
+            Error::Serialize { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> Error + '_ {
+    move |source| Error::Io {
+        path: path.to_owned(),
+        source,
+    }
+}
+
+impl Profile {
+    /// Checks that every key needed to run commands is set.
+    pub fn validate(&self, profile: &str) -> Result<(), Error> {
+        let mut required = vec![("path", &self.path)];
+        if self.executor == Executor::Ssh {
+            required.push(("username", &self.username));
+            required.push(("hostname", &self.hostname));
+        }
+        match required.into_iter().find(|(_, value)| value.is_empty()) {
+            Some((key, _)) => Err(Error::MissingKey {
+                profile: profile.to_owned(),
+                key,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+fn profile_dir() -> Result<PathBuf, Error> {
+    home_dir()
+        .map(|a| a.join("pcode-cli/docker"))
+        .ok_or(Error::NoHome)
+}
+
+fn profile_path(profile: &str, extension: &str) -> Result<PathBuf, Error> {
+    Ok(profile_dir()?.join(format!("{}.{}", profile, extension)))
+}
+
+/// Lists saved profiles. A missing profile directory just means there are
+/// none yet.
+pub fn load_name() -> Result<Vec<String>, Error> {
+    let target_path = profile_dir()?;
     let path = target_path.as_path();
 
     let mut files: Vec<String> = Vec::new();
 
-    for entry in fs::read_dir(path).unwrap() {
-        let entry = entry.unwrap();
-        let name = entry.file_name();
-        let file_path = entry.path();
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(files),
+        Err(e) => return Err(io_error(path)(e)),
+    };
 
-        if file_path.extension() != Some("env".as_ref()) {
+    for entry in entries {
+        let file_path = entry.map_err(io_error(path))?.path();
+
+        if file_path.extension() != Some("toml".as_ref()) {
             continue;
         }
 
-        let mut name = name.into_string().unwrap();
-        let name_split: Vec<&str> = name.split('.').collect();
-        name = String::from(name_split[0]);
-
-        files.push(name);
+        if let Some(name) = file_path.file_stem() {
+            files.push(name.to_string_lossy().into_owned());
+        }
     }
 
-    files
+    files.sort();
+    Ok(files)
 }
 
-pub fn load(profile: String) -> Vec<String> {
-    let target_path = home_dir()
-        .and_then(|a| Some(a.join(format!("pcode-cli/docker/{}.env", profile))))
-        .unwrap();
+/// Reads a profile without checking it, for editing.
+pub fn read(profile: String) -> Result<Profile, Error> {
+    let target_path = profile_path(&profile, "toml")?;
+
+    let content = fs::read_to_string(&target_path).map_err(io_error(&target_path))?;
 
-    env::remove_var("USERNAME");
-    env::remove_var("HOSTNAME");
-    env::remove_var("TARGET_PATH");
+    toml::from_str(&content).map_err(|source| Error::Parse { profile, source })
+}
+
+/// Reads a profile that is about to be used to run commands.
+pub fn load(profile: String) -> Result<Profile, Error> {
+    let config = read(profile.clone())?;
+    config.validate(&profile)?;
+    Ok(config)
+}
 
-    dotenv::from_path(target_path.as_path()).expect("profile does not existed");
+pub fn create(profile: String, config: &Profile) -> Result<(), Error> {
+    let target_path = profile_path(&profile, "toml")?;
+    let path = target_path.as_path();
+    let prefix = profile_dir()?;
 
-    let username = env::var("USERNAME").unwrap();
-    let hostname = env::var("HOSTNAME").unwrap();
-    let path = env::var("TARGET_PATH").unwrap();
+    fs::create_dir_all(&prefix).map_err(io_error(&prefix))?;
 
-    vec![username, hostname, path]
+    let content = toml::to_string(config).map_err(|source| Error::Serialize { profile, source })?;
+    fs::write(path, content).map_err(io_error(path))
 }
 
-pub fn create(profile: String, username: String, hostname: String, c_path: String) {
-    let target_path = home_dir()
-        .and_then(|a| Some(a.join(format!("pcode-cli/docker/{}.env", profile))))
-        .unwrap();
+pub fn remove(profile: String) -> Result<(), Error> {
+    let target_path = profile_path(&profile, "toml")?;
     let path = target_path.as_path();
-    let prefix = path.parent().unwrap();
 
-    fs::create_dir_all(prefix).unwrap();
-    fs::remove_file(path).unwrap_or(());
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(io_error(path)(e)),
+        _ => Ok(()),
+    }
+}
+
+/// Converts every `<name>.env` profile written by older versions into
+/// `<name>.toml`, keeping the original as `<name>.env.bak`.
+pub fn migrate() -> Result<(), Error> {
+    let target_path = profile_dir()?;
+    let entries = match fs::read_dir(&target_path) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
 
-    let mut file = fs::File::create(path).unwrap();
-    file.write(
-        format!(
-            "USERNAME={}\nHOSTNAME={}\nTARGET_PATH={}",
-            username, hostname, c_path
-        )
-        .as_bytes(),
-    )
-    .unwrap();
+    for entry in entries {
+        let file_path = entry.map_err(io_error(&target_path))?.path();
+
+        if file_path.extension() != Some("env".as_ref()) {
+            continue;
+        }
+
+        let name = match file_path.file_stem() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => continue,
+        };
+        if profile_path(&name, "toml")?.exists() {
+            continue;
+        }
+
+        create(name.clone(), &load_env(&file_path)?)?;
+        fs::rename(&file_path, file_path.with_extension("env.bak"))
+            .map_err(io_error(&file_path))?;
+    }
+    Ok(())
+}
+
+fn load_env(path: &Path) -> Result<Profile, Error> {
+    let content = fs::read_to_string(path).map_err(io_error(path))?;
+    let mut values = parse_env(&content);
+
+    let mut take = |key: &str| values.remove(key).unwrap_or_default();
+    Ok(Profile {
+        username: take("USERNAME"),
+        hostname: take("HOSTNAME"),
+        path: take("TARGET_PATH"),
+        executor: match take("EXECUTOR").as_str() {
+            "local" => Executor::Local,
+            _ => Executor::Ssh,
+        },
+        ..Profile::default()
+    })
+}
+
+/// Parses `KEY=value` lines the way the old `.env` profiles were written,
+/// without touching the process environment.
+fn parse_env(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let key = key.trim().trim_start_matches("export ").trim();
+            let value = value.trim();
+            let value = match (value.chars().next(), value.chars().last()) {
+                (Some(q @ ('"' | '\'')), Some(e)) if value.len() > 1 && q == e => {
+                    &value[1..value.len() - 1]
+                }
+                _ => value,
+            };
+            (key.to_owned(), value.to_owned())
+        })
+        .collect()
+}
+
+pub fn save_log(profile: &str, lines: &[String]) -> Result<PathBuf, Error> {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let prefix = home_dir()
+        .map(|a| a.join("pcode-cli/logs"))
+        .ok_or(Error::NoHome)?;
+    let target_path = prefix.join(format!("{}-{}.log", profile, seconds));
+
+    fs::create_dir_all(&prefix).map_err(io_error(&prefix))?;
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    fs::write(&target_path, content).map_err(io_error(&target_path))?;
+
+    Ok(target_path)
 }
 
-pub fn remove(profile: String) {
-    let target_path = home_dir()
-        .and_then(|a| Some(a.join(format!("pcode-cli/docker/{}.env", profile))))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_profile_round_trips() {
+        let profile = Profile {
+            username: String::from("deploy"),
+            hostname: String::from("example.com"),
+            path: String::from("~/stack"),
+            executor: Executor::Ssh,
+            ..Profile::default()
+        };
+        let content = toml::to_string(&profile).unwrap();
+        assert_eq!(
+            content,
+            "username = \"deploy\"\nhostname = \"example.com\"\npath = \"~/stack\"\nexecutor = \"ssh\"\n"
+        );
+        assert_eq!(toml::from_str::<Profile>(&content).unwrap(), profile);
+    }
+
+    #[test]
+    fn unknown_executors_are_rejected() {
+        let parse =
+            |executor: &str| toml::from_str::<Profile>(&format!("executor = \"{}\"", executor));
+        assert_eq!(parse("local").unwrap().executor, Executor::Local);
+        assert_eq!(parse("").unwrap().executor, Executor::Ssh);
+        assert!(parse("lcoal").is_err());
+    }
+
+    #[test]
+    fn env_files_are_parsed_without_the_process_environment() {
+        let values = parse_env("# old profile\nUSERNAME=deploy\nexport HOSTNAME = \"example.com\"\n\nTARGET_PATH='~/my stack'\n");
+        assert_eq!(values.len(), 3);
+        assert_eq!(values["USERNAME"], "deploy");
+        assert_eq!(values["HOSTNAME"], "example.com");
+        assert_eq!(values["TARGET_PATH"], "~/my stack");
+    }
+
+    #[test]
+    fn missing_keys_are_reported() {
+        let profile = Profile {
+            hostname: String::from("example.com"),
+            path: String::from("/srv/app"),
+            ..Profile::default()
+        };
+        assert!(matches!(
+            profile.validate("prod"),
+            Err(Error::MissingKey {
+                key: "username",
+                ..
+            })
+        ));
+
+        let local = Profile {
+            executor: Executor::Local,
+            path: String::from("/srv/app"),
+            ..Profile::default()
+        };
+        assert!(local.validate("laptop").is_ok());
+    }
+
+    #[test]
+    fn parse_errors_name_the_profile() {
+        let source = toml::from_str::<Profile>("port = \"ssh\"").unwrap_err();
+        let error = Error::Parse {
+            profile: String::from("prod"),
+            source,
+        };
+        assert!(error.to_string().starts_with("profile `prod` is invalid: "));
+    }
+
+    #[test]
+    fn optional_fields_are_read() {
+        let profile: Profile = toml::from_str(
+            r#"
+            hostname = "example.com"
+            path = "/srv/app"
+            port = 2222
+            identity_file = "~/.ssh/deploy"
+            ssh_options = ["ServerAliveInterval=30"]
+            compose_files = ["docker-compose.yml", "docker-compose.prod.yml"]
+            default_services = ["web"]
+            notes = "staging box"
+            "#,
+        )
         .unwrap();
-    let path = target_path.as_path();
-    let prefix = path.parent().unwrap();
+        assert_eq!(profile.port, Some(2222));
+        assert_eq!(profile.ssh_options, vec!["ServerAliveInterval=30"]);
+        assert_eq!(profile.compose_files.len(), 2);
+        assert_eq!(profile.default_services, vec!["web"]);
+        assert_eq!(profile.notes, "staging box");
+        assert!(profile.jump_host.is_none());
+        assert_eq!(profile.up, UpOptions::default());
+    }
 
-    fs::create_dir_all(prefix).unwrap();
-    fs::remove_file(path).unwrap_or(());
+    #[test]
+    fn up_options_are_remembered() {
+        let profile = Profile {
+            path: String::from("/srv/app"),
+            up: UpOptions {
+                force_recreate: true,
+                scale: vec![String::from("worker=2")],
+                ..UpOptions::default()
+            },
+            ..Profile::default()
+        };
+        let content = toml::to_string(&profile).unwrap();
+        assert!(content.contains("[up]"));
+        assert_eq!(toml::from_str::<Profile>(&content).unwrap(), profile);
+
+        let missing = Profile {
+            up: UpOptions {
+                build: None,
+                ..UpOptions::default()
+            },
+            ..profile
+        };
+        let content = toml::to_string(&missing).unwrap();
+        assert_eq!(toml::from_str::<Profile>(&content).unwrap(), missing);
+    }
 }
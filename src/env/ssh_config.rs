@@ -0,0 +1,276 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use dirs::home_dir;
+
+/// A concrete `Host` alias from the user's ssh config, with the settings ssh
+/// would use for it.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SshHost {
+    pub alias: String,
+    pub hostname: String,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+}
+
+/// Settings from one `Host` section, in file order.
+#[derive(Debug, Default)]
+struct Section {
+    patterns: Vec<String>,
+    hostname: Option<String>,
+    user: Option<String>,
+    port: Option<u16>,
+}
+
+/// Nested `Include`s deeper than this are ignored, as ssh does.
+static INCLUDE_DEPTH: usize = 16;
+
+/// Reads `~/.ssh/config` and everything it includes. A missing or unreadable
+/// file just yields no hosts.
+pub fn hosts() -> Vec<SshHost> {
+    let ssh_dir = match home_dir() {
+        Some(home) => home.join(".ssh"),
+        None => return Vec::new(),
+    };
+    let mut sections = vec![Section {
+        patterns: vec![String::from("*")],
+        ..Section::default()
+    }];
+    read(&ssh_dir.join("config"), &ssh_dir, 0, &mut sections);
+    resolve(&sections)
+}
+
+fn read(path: &Path, ssh_dir: &Path, depth: usize, sections: &mut Vec<Section>) {
+    if let Ok(content) = fs::read_to_string(path) {
+        parse(&content, ssh_dir, depth, sections);
+    }
+}
+
+fn parse(content: &str, ssh_dir: &Path, depth: usize, sections: &mut Vec<Section>) {
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((keyword, value)) => (
+                keyword.to_ascii_lowercase(),
+                value.trim_start_matches(|c: char| c.is_whitespace() || c == '='),
+            ),
+            None => continue,
+        };
+        let value = value.trim().trim_matches('"');
+
+        match keyword.as_str() {
+            "host" => sections.push(Section {
+                patterns: value.split_whitespace().map(String::from).collect(),
+                ..Section::default()
+            }),
+            // `Match` conditions cannot be evaluated here; skip the section.
+            "match" => sections.push(Section::default()),
+            "include" if depth < INCLUDE_DEPTH => {
+                for pattern in value.split_whitespace() {
+                    for path in include_paths(pattern, ssh_dir) {
+                        read(&path, ssh_dir, depth + 1, sections);
+                    }
+                }
+            }
+            _ => {
+                let section = sections.last_mut().unwrap();
+                match keyword.as_str() {
+                    "hostname" => {
+                        section.hostname.get_or_insert_with(|| value.to_owned());
+                    }
+                    "user" => {
+                        section.user.get_or_insert_with(|| value.to_owned());
+                    }
+                    "port" if section.port.is_none() => section.port = value.parse().ok(),
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// Expands an `Include` argument: `~` is the home directory, relative paths
+/// are relative to `~/.ssh`, and the file name may contain `*` and `?`.
+fn include_paths(pattern: &str, ssh_dir: &Path) -> Vec<PathBuf> {
+    let path = match pattern.strip_prefix("~/") {
+        Some(rest) => ssh_dir.parent().unwrap_or(ssh_dir).join(rest),
+        None => ssh_dir.join(pattern),
+    };
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => return Vec::new(),
+    };
+    if !name.contains(['*', '?']) {
+        return vec![path];
+    }
+
+    let dir = path.parent().unwrap_or(ssh_dir);
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter(|e| matches(&name, &e.file_name().to_string_lossy()))
+            .map(|e| e.path())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    paths.sort();
+    paths
+}
+
+/// Shell-style match supporting `*` and `?`, as used by `Host` and `Include`.
+fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((sp, st)) => {
+                    p = sp + 1;
+                    t = st + 1;
+                    star = Some((sp, st + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn is_concrete(pattern: &str) -> bool {
+    !pattern.contains(['*', '?', '!'])
+}
+
+fn section_applies(section: &Section, alias: &str) -> bool {
+    let negated = section
+        .patterns
+        .iter()
+        .filter_map(|p| p.strip_prefix('!'))
+        .any(|p| matches(p, alias));
+    !negated && section.patterns.iter().any(|p| matches(p, alias))
+}
+
+/// Lists every concrete alias once, taking the first value for each setting
+/// from the sections that apply to it, the way ssh does.
+fn resolve(sections: &[Section]) -> Vec<SshHost> {
+    let mut hosts: Vec<SshHost> = Vec::new();
+    for alias in sections.iter().flat_map(|s| &s.patterns) {
+        if !is_concrete(alias) || hosts.iter().any(|h| &h.alias == alias) {
+            continue;
+        }
+        let applying: Vec<&Section> = sections
+            .iter()
+            .filter(|s| section_applies(s, alias))
+            .collect();
+        hosts.push(SshHost {
+            alias: alias.clone(),
+            hostname: applying
+                .iter()
+                .find_map(|s| s.hostname.clone())
+                .unwrap_or_else(|| alias.clone()),
+            user: applying.iter().find_map(|s| s.user.clone()),
+            port: applying.iter().find_map(|s| s.port),
+        });
+    }
+    hosts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hosts_in(content: &str, ssh_dir: &Path) -> Vec<SshHost> {
+        let mut sections = vec![Section {
+            patterns: vec![String::from("*")],
+            ..Section::default()
+        }];
+        parse(content, ssh_dir, 0, &mut sections);
+        resolve(&sections)
+    }
+
+    #[test]
+    fn hosts_take_the_first_matching_value() {
+        let hosts = hosts_in(
+            "
+            User fallback
+            Host prod prod-db
+                HostName 10.0.0.5
+                Port=2222
+            Host dev
+                hostname dev.example.com
+                User alice
+            Host *.example.com !skip.example.com
+                User ops
+            Host *
+                Port 22
+            ",
+            Path::new("/nonexistent"),
+        );
+        assert_eq!(
+            hosts,
+            vec![
+                SshHost {
+                    alias: String::from("prod"),
+                    hostname: String::from("10.0.0.5"),
+                    user: Some(String::from("fallback")),
+                    port: Some(2222),
+                },
+                SshHost {
+                    alias: String::from("prod-db"),
+                    hostname: String::from("10.0.0.5"),
+                    user: Some(String::from("fallback")),
+                    port: Some(2222),
+                },
+                SshHost {
+                    alias: String::from("dev"),
+                    hostname: String::from("dev.example.com"),
+                    user: Some(String::from("fallback")),
+                    port: Some(22),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn includes_are_followed() {
+        let dir = std::env::temp_dir().join(format!("compose-tui-ssh-{}", std::process::id()));
+        fs::create_dir_all(dir.join("conf.d")).unwrap();
+        fs::write(dir.join("conf.d/a.conf"), "Host a\n  User deploy\n").unwrap();
+        fs::write(dir.join("conf.d/b.conf"), "Host b\n  Include c\n").unwrap();
+        fs::write(dir.join("c"), "Port 2200\n").unwrap();
+
+        let hosts = hosts_in("Include conf.d/*.conf\n", &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let aliases: Vec<(&str, Option<&str>, Option<u16>)> = hosts
+            .iter()
+            .map(|h| (h.alias.as_str(), h.user.as_deref(), h.port))
+            .collect();
+        assert_eq!(
+            aliases,
+            vec![("a", Some("deploy"), None), ("b", None, Some(2200))]
+        );
+    }
+
+    #[test]
+    fn wildcards_match() {
+        assert!(matches("*.example.com", "db.example.com"));
+        assert!(matches("web-?", "web-1"));
+        assert!(!matches("web-?", "web-10"));
+        assert!(matches("*", ""));
+    }
+}
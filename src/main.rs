@@ -1,288 +1,344 @@
+mod args;
 mod cli;
 mod env;
-mod event;
-mod ui;
+mod screen;
+mod terminal;
 
-use std::io;
+use std::{io, time::Duration};
 
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, Event},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use tui::{
-    backend::{Backend, CrosstermBackend},
-    widgets::ListState,
-    Frame, Terminal,
-};
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use screen::{menu::Menu, output::Output, popup, profile, Navigator, Transition};
+use terminal::events::{Events, Message};
+use tui::{backend::CrosstermBackend, Terminal};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum Action {
     None,
     Up,
     Down,
     Start,
     Stop,
+    Restart,
+    Pull,
+    Build,
+    Ps,
 }
 
-#[derive(Debug)]
-pub enum CurrentBlock {
-    Main,
-    Env,
-    Up,
-    Down,
-    Start,
-    Stop,
-    EnvEdit,
-    UpTarget,
-    DownRmi,
-    StartTarget,
-    StopTarget,
-}
-
-impl CurrentBlock {
-    fn from_usize(u: usize) -> CurrentBlock {
-        match u {
-            0 => CurrentBlock::Main,
-            1 => CurrentBlock::Env,
-            2 => CurrentBlock::Up,
-            3 => CurrentBlock::Down,
-            4 => CurrentBlock::Start,
-            5 => CurrentBlock::Stop,
-            6 => CurrentBlock::EnvEdit,
-            7 => CurrentBlock::UpTarget,
-            8 => CurrentBlock::DownRmi,
-            9 => CurrentBlock::StartTarget,
-            10 => CurrentBlock::StopTarget,
-            _ => unreachable!(),
-        }
+impl Action {
+    /// Whether the action removes containers, images or volumes.
+    fn is_destructive(&self) -> bool {
+        matches!(self, Action::Down)
     }
-}
 
-#[derive(Debug)]
-pub enum InputMode {
-    Normal,
-    Insert,
+    fn changes_state(&self) -> bool {
+        !matches!(self, Action::None | Action::Ps)
+    }
 }
 
+/// State shared by every screen: the selected profile, the action about to
+/// run and what has to be reported or done outside the screen stack.
 #[derive(Debug)]
 pub struct App {
-    selected_state: SelectedState,
-    current_block: CurrentBlock,
     user_profile: UserProfile,
-    list_profile: Vec<String>,
-    input_mode: InputMode,
     action: Action,
+    error: Option<String>,
+    confirm: Option<Confirm>,
+    /// Service to open a shell in once the TUI has handed over the terminal.
+    exec: Option<String>,
+    failure: Option<cli::CommandResult>,
+    /// Help entries as last drawn and the key each one presses when clicked.
+    buttons: Vec<(tui::layout::Rect, KeyCode)>,
 }
 
 impl Default for App {
     fn default() -> App {
         App {
-            selected_state: SelectedState::default(),
-            current_block: CurrentBlock::Main,
             user_profile: UserProfile::default(),
-            list_profile: Vec::new(),
-            input_mode: InputMode::Normal,
             action: Action::None,
+            error: None,
+            confirm: None,
+            exec: None,
+            failure: None,
+            buttons: Vec::new(),
         }
     }
 }
 
-#[derive(Debug)]
-pub struct UserProfile {
-    profile: String,
-    username: String,
-    hostname: String,
-    path: String,
-    rmi: String,
-    target: String,
-}
+impl App {
+    /// Keeps the error of a failed profile operation for the error popup.
+    fn report<T>(&mut self, result: Result<T, env::Error>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error = Some(e.to_string());
+                None
+            }
+        }
+    }
 
-impl UserProfile {
-    fn set(
-        &mut self,
-        profile: String,
-        username: String,
-        hostname: String,
-        path: String,
-        rmi: String,
-        target: String,
-    ) {
-        self.profile = profile;
-        self.username = username;
-        self.hostname = hostname;
-        self.path = path;
-        self.rmi = rmi;
-        self.target = target;
+    /// Opens a shell in `service`, attached to the terminal. A failing shell
+    /// keeps its last output on screen until Enter is pressed.
+    fn run_exec(&mut self, service: &str) {
+        let executor = cli::executor::from_profile(&self.user_profile);
+        let shell = self.user_profile.config.shells.get(service);
+        match cli::exec(executor.as_ref(), service, shell.map(String::as_str)) {
+            Ok(Some(0)) => (),
+            Ok(code) => {
+                match code {
+                    Some(code) => println!("\n{} exited with code {}", service, code),
+                    None => println!("\n{} was killed", service),
+                }
+                println!("Press Enter to return");
+                let _ = io::stdin().read_line(&mut String::new());
+            }
+            Err(e) => self.error = Some(format!("could not start the shell: {}", e)),
+        }
+    }
+
+    /// Runs the selected action, first asking for confirmation if it is
+    /// destructive or the profile is protected.
+    fn run_action(&mut self) -> Option<Transition> {
+        self.confirm_action(Then::RunAction)
     }
-    fn set_rmi(&mut self, rmi: String) {
-        self.rmi = rmi;
+
+    /// Like [`App::run_action`], but saves the profile right before the
+    /// action runs, so a cancelled confirmation leaves it untouched.
+    fn save_and_run_action(&mut self) -> Option<Transition> {
+        self.confirm_action(Then::SaveAndRunAction)
     }
-}
 
-impl Default for UserProfile {
-    fn default() -> UserProfile {
-        UserProfile {
-            profile: String::new(),
-            username: String::new(),
-            hostname: String::new(),
-            path: String::new(),
-            rmi: String::new(),
-            target: String::new(),
+    fn confirm_action(&mut self, then: Then) -> Option<Transition> {
+        let protected = self.user_profile.config.protected;
+        if self.action.is_destructive() || (protected && self.action.changes_state()) {
+            self.confirm = self.preview_action().map(|command| Confirm {
+                command,
+                expected: match protected {
+                    true => self.user_profile.profile.clone(),
+                    false => String::new(),
+                },
+                then,
+                ..Confirm::default()
+            });
+            return None;
         }
+        self.confirmed(then)
     }
-}
 
-#[derive(Debug)]
-pub struct SelectedState {
-    max: usize,
-    current: ListState,
-}
+    /// Does what a confirmation was asked for.
+    fn confirmed(&mut self, then: Then) -> Option<Transition> {
+        match then {
+            Then::RunAction => self.start_action(),
+            Then::SaveAndRunAction => {
+                let profile = &self.user_profile;
+                self.report(env::create(profile.profile.clone(), &profile.config))?;
+                self.start_action()
+            }
+            Then::SaveProfile { close } => profile::save(self, close),
+            Then::RemoveProfile(name) => {
+                self.report(env::remove(name))?;
+                Some(Transition::Replace(profile::Profiles::open(self)))
+            }
+            Then::Pop => Some(Transition::Pop),
+        }
+    }
 
-impl SelectedState {
-    fn set_max(&mut self, max: usize) {
-        self.max = max;
+    /// Opens the output of the selected action, now running.
+    fn start_action(&mut self) -> Option<Transition> {
+        let executor = cli::executor::from_profile(&self.user_profile);
+        let receiver = self.action_job(executor.as_ref())?;
+        Some(Transition::Push(Output::open(self.action, receiver)))
     }
 
-    fn set_current(&mut self, current: usize) {
-        self.current.select(Some(current));
+    /// The `docker compose` line the selected action would run.
+    fn preview_action(&self) -> Option<String> {
+        let executor = cli::executor::Recording::default();
+        self.action_job(&executor)?;
+        let args: Vec<String> = cli::command::global_args(&self.user_profile.config)
+            .iter()
+            .chain(&executor.calls.borrow()[0])
+            .map(|a| cli::command::quote(a))
+            .collect();
+        Some(format!("docker compose {}", args.join(" ")))
     }
 
-    fn next(&mut self) {
-        let i = match self.current.selected() {
-            Some(i) => {
-                if i >= self.max - 1 {
-                    i
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
+    fn action_job(&self, executor: &dyn cli::Executor) -> Option<cli::Job> {
+        let job = match self.action {
+            Action::Up => cli::up(
+                executor,
+                Some(self.user_profile.target.clone()),
+                &self.user_profile.config.up,
+            ),
+            Action::Down => cli::down(executor, &self.user_profile.down),
+            Action::Start => cli::start(executor, Some(self.user_profile.target.clone())),
+            Action::Stop => cli::stop(executor, Some(self.user_profile.target.clone())),
+            Action::Restart => cli::restart(executor, Some(self.user_profile.target.clone())),
+            Action::Pull => cli::pull(executor, Some(self.user_profile.target.clone())),
+            Action::Build => cli::build(
+                executor,
+                Some(self.user_profile.target.clone()),
+                self.user_profile.no_cache,
+            ),
+            Action::Ps => cli::ps(executor, Some(self.user_profile.target.clone())),
+            Action::None => return None,
         };
-        self.current.select(Some(i));
+        Some(job)
     }
+}
 
-    fn prev(&mut self) {
-        let i = match self.current.selected() {
-            Some(i) => {
-                if i == 0 {
-                    0
-                } else {
-                    i - 1
-                }
-            }
-            None => 0,
-        };
-        self.current.select(Some(i));
+/// A command or change waiting for the user to confirm it.
+#[derive(Debug, Default)]
+pub struct Confirm {
+    /// The command line for actions, otherwise what is about to happen.
+    command: String,
+    /// Text that has to be typed to confirm; empty means `y` is enough.
+    expected: String,
+    typed: String,
+    then: Then,
+}
+
+/// What happens once a confirmation is accepted.
+#[derive(Debug, Default)]
+pub enum Then {
+    #[default]
+    RunAction,
+    SaveAndRunAction,
+    /// Saves the profile being edited, and closes the screen if `close`.
+    SaveProfile {
+        close: bool,
+    },
+    RemoveProfile(String),
+    /// Closes the current screen, e.g. to stop the command it is running.
+    Pop,
+}
+
+impl Then {
+    fn is_action(&self) -> bool {
+        matches!(self, Then::RunAction | Then::SaveAndRunAction)
     }
 }
 
-impl Default for SelectedState {
-    fn default() -> SelectedState {
-        SelectedState {
-            max: 0,
-            current: ListState::default(),
+#[derive(Debug, Default)]
+pub struct UserProfile {
+    profile: String,
+    config: env::Profile,
+    down: cli::DownOptions,
+    target: String,
+    no_cache: bool,
+}
+
+impl UserProfile {
+    fn set(&mut self, profile: String, config: env::Profile) {
+        self.profile = profile;
+        self.down = cli::DownOptions::default();
+        self.target = config.default_services.join(" ");
+        self.no_cache = false;
+        self.config = config;
+    }
+    /// Adds `service` to, or removes it from, the target.
+    fn toggle_service(&mut self, service: &str) {
+        let mut services = cli::command::services(&self.target);
+        match services.iter().position(|s| s == service) {
+            Some(i) => {
+                services.remove(i);
+            }
+            None => services.push(service.to_owned()),
         }
+        self.target = services.join(" ");
     }
 }
 
+/// How often views redraw when nothing else happens, to keep timers such as
+/// "refreshed 3s ago" and periodic polls moving.
+static TICK_RATE: Duration = Duration::from_millis(250);
+
 fn main() {
-    enable_raw_mode().unwrap();
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture).unwrap();
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend).unwrap();
+    if let Err(e) = env::migrate() {
+        eprintln!("warning: could not migrate old profiles: {}", e);
+    }
 
-    let mut app = App::default();
-    let res = run_app(&mut terminal, &mut app);
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    match args::parse(&arguments) {
+        Ok(Some(subcommand)) => std::process::exit(args::run(subcommand)),
+        Ok(None) => (),
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, args::USAGE);
+            std::process::exit(2);
+        }
+    }
 
-    disable_raw_mode().unwrap();
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )
-    .unwrap();
-    terminal.show_cursor().unwrap();
+    cli::executor::set_batch_mode();
+    cli::multiplex::start();
+    let res = terminal::Guard::new().and_then(|guard| {
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        let mut app = App::default();
+        run_app(&mut terminal, &guard, &mut app).map(|_| app)
+    });
+    cli::multiplex::end();
 
-    if res.is_ok() {
-        match app.action {
-            Action::Up => cli::up(
-                app.user_profile.username.clone(),
-                app.user_profile.hostname.clone(),
-                app.user_profile.path.clone(),
-                Some(app.user_profile.target.clone()),
-            ),
-            Action::Down => match app.user_profile.rmi.as_str() {
-                "" => cli::down(
-                    app.user_profile.username.clone(),
-                    app.user_profile.hostname.clone(),
-                    app.user_profile.path.clone(),
-                    None,
-                ),
-                _ => cli::down(
-                    app.user_profile.username.clone(),
-                    app.user_profile.hostname.clone(),
-                    app.user_profile.path.clone(),
-                    Some(app.user_profile.rmi.clone()),
-                ),
-            },
-            Action::Start => cli::start(
-                app.user_profile.username.clone(),
-                app.user_profile.hostname.clone(),
-                app.user_profile.path.clone(),
-                Some(app.user_profile.target.clone()),
-            ),
-            Action::Stop => cli::stop(
-                app.user_profile.username.clone(),
-                app.user_profile.hostname.clone(),
-                app.user_profile.path.clone(),
-                Some(app.user_profile.target.clone()),
-            ),
-            _ => (),
+    let app = match res {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Some(failure) = app.failure {
+        for line in &failure.output {
+            eprintln!("{}", line);
         }
+        std::process::exit(failure.exit_code());
     }
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: &mut App) -> io::Result<()> {
-    app.selected_state.current.select(Some(0));
-    loop {
-        terminal.draw(|f| ui(f, &mut app)).unwrap();
+fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    guard: &terminal::Guard,
+    app: &mut App,
+) -> io::Result<()> {
+    let events = Events::new(TICK_RATE);
+    cli::set_waker(events.waker());
+    let mut navigator = Navigator::new(Menu::open());
+    while !navigator.is_done() {
+        navigator.poll(app);
+        terminal.draw(|f| {
+            navigator.render(f, app);
+            popup::confirm(f, app);
+            popup::error(f, app);
+        })?;
 
-        if let Ok(Event::Key(key)) = crossterm::event::read() {
-            let result = match app.current_block {
-                CurrentBlock::Main => event::main(&mut app, key),
-                CurrentBlock::Env => event::env(&mut app, key),
-                CurrentBlock::EnvEdit => event::env_edit(&mut app, key),
-                CurrentBlock::Up => event::up(&mut app, key),
-                CurrentBlock::UpTarget => event::up_target(&mut app, key),
-                CurrentBlock::Down => event::down(&mut app, key),
-                CurrentBlock::DownRmi => event::down_rmi(&mut app, key),
-                CurrentBlock::Start => event::start(&mut app, key),
-                CurrentBlock::StartTarget => event::start_target(&mut app, key),
-                CurrentBlock::Stop => event::stop(&mut app, key),
-                CurrentBlock::StopTarget => event::stop_target(&mut app, key),
-            };
-            match result {
-                Some(r) => return r,
-                None => (),
+        if guard.suspend_requested() {
+            events.paused(|| guard.suspend(terminal))?;
+        }
+        let key = match events.next() {
+            Message::Input(Event::Key(key)) => key,
+            Message::Input(Event::Mouse(mouse)) => match navigator.mouse_key(app, mouse) {
+                Some(key) => key,
+                None => continue,
+            },
+            Message::Failed(e) => return Err(e),
+            _ => continue,
+        };
+        if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            events.paused(|| guard.suspend(terminal))?;
+            continue;
+        }
+        if key.code == KeyCode::F(2) {
+            guard.toggle_mouse()?;
+            continue;
+        }
+        if app.error.take().is_some() {
+            continue;
+        }
+        if app.confirm.is_some() {
+            if let Some(transition) = popup::confirm_event(app, key) {
+                navigator.apply(app, transition);
             }
+            continue;
+        }
+        navigator.handle_event(app, key);
+        if let Some(service) = app.exec.take() {
+            events.paused(|| guard.pause(terminal, || app.run_exec(&service)))?;
         }
     }
-}
-
-fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
-    match app.current_block {
-        CurrentBlock::Main => ui::menu(f, app),
-        CurrentBlock::Env => ui::env(f, app),
-        CurrentBlock::EnvEdit => ui::env_edit(f, app),
-        CurrentBlock::Up => ui::up(f, app),
-        CurrentBlock::UpTarget => ui::up_target(f, app),
-        CurrentBlock::Down => ui::down(f, app),
-        CurrentBlock::DownRmi => ui::down_rmi(f, app),
-        CurrentBlock::Start => ui::start(f, app),
-        CurrentBlock::StartTarget => ui::start_target(f, app),
-        CurrentBlock::Stop => ui::stop(f, app),
-        CurrentBlock::StopTarget => ui::stop_target(f, app),
-    };
+    Ok(())
 }
@@ -0,0 +1,151 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::text::Span;
+
+use super::{
+    edit_text, help, layout, list, profile, target::ServiceList, Frame, InputMode, Screen,
+    SelectedState, Transition,
+};
+use crate::App;
+
+/// Picks the service to open a shell in. Once the services are listed, `s`
+/// edits the shell remembered for the highlighted one; before that, or if
+/// they cannot be listed, the service name is typed in.
+pub struct ExecTarget {
+    services: ServiceList,
+    selected: SelectedState,
+    input_mode: InputMode,
+}
+
+impl ExecTarget {
+    pub fn open(app: &mut App) -> Box<dyn Screen> {
+        Box::new(ExecTarget {
+            services: ServiceList::fetch(&app.user_profile),
+            selected: SelectedState::new(),
+            input_mode: InputMode::Normal,
+        })
+    }
+}
+
+impl Screen for ExecTarget {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        let names = &self.services.names;
+        self.selected.set_max(match names.len() {
+            0 => 2,
+            n => n,
+        });
+        let selected = self.selected.selected();
+        match self.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+                KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+                KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+                KeyCode::Enter | KeyCode::Char('e') => match names.get(selected) {
+                    Some(name) => app.exec = Some(name.clone()),
+                    None if selected == 0 => self.input_mode = InputMode::Insert,
+                    None => {
+                        let service = app.user_profile.target.trim().to_owned();
+                        if !service.is_empty() {
+                            app.exec = Some(service);
+                        }
+                    }
+                },
+                KeyCode::Char('s') if !names.is_empty() => self.input_mode = InputMode::Insert,
+                _ => (),
+            },
+            InputMode::Insert if names.is_empty() => {
+                if !edit_text(&mut app.user_profile.target, key) {
+                    self.input_mode = InputMode::Normal;
+                }
+            }
+            InputMode::Insert => {
+                let shells = &mut app.user_profile.config.shells;
+                let name = match names.get(selected) {
+                    Some(name) => name,
+                    None => {
+                        self.input_mode = InputMode::Normal;
+                        return None;
+                    }
+                };
+                match key.code {
+                    KeyCode::Enter | KeyCode::Esc => {
+                        self.input_mode = InputMode::Normal;
+                        if shells.get(name).is_some_and(|s| s.trim().is_empty()) {
+                            shells.remove(name);
+                        }
+                        return profile::confirm_save(app, false);
+                    }
+                    KeyCode::Char(c) => shells.entry(name.clone()).or_default().push(c),
+                    KeyCode::Backspace => {
+                        if let Some(shell) = shells.get_mut(name) {
+                            shell.pop();
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+
+        let names = &self.services.names;
+        let shells = &app.user_profile.config.shells;
+        let mut items: Vec<String> = Vec::new();
+        if names.is_empty() {
+            items.push(format!("service: {}", app.user_profile.target));
+            items.push(String::from("Open Shell"));
+        } else {
+            for name in names {
+                let shell = match shells.get(name) {
+                    Some(shell) => shell.as_str(),
+                    None => "bash/sh",
+                };
+                items.push(format!("{} ({})", name, shell));
+            }
+        }
+
+        list(
+            f,
+            chunks[0],
+            Span::from("Exec"),
+            &items,
+            &mut self.selected,
+            &self.input_mode,
+        );
+
+        let status = self
+            .services
+            .status("S - Edit Shell", "Type the Service Name");
+        let lines = match self.input_mode {
+            InputMode::Normal => vec![
+                status.as_str(),
+                "",
+                "Up/K Down/J - Navigate",
+                "Enter/E - Open Shell",
+                "Esc/Q - Return",
+            ],
+            InputMode::Insert => vec!["Backspace - Delete", "Enter/Esc - Return"],
+        };
+        help(f, app, chunks[1], &lines);
+    }
+
+    /// Keeps the selection on a service once the list replaces the two
+    /// rows shown while loading, and stops typing a service name there.
+    fn poll(&mut self, _app: &mut App) {
+        let loading = self.services.names.is_empty();
+        self.services.poll();
+        let len = self.services.names.len();
+        if loading && len > 0 {
+            self.input_mode = InputMode::Normal;
+        }
+        if len > 0 && self.selected.selected() >= len {
+            self.selected.set_current(len - 1);
+        }
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
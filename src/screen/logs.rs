@@ -0,0 +1,354 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    layout::{Constraint, Layout},
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use super::{edit_text, help, layout, list, Frame, InputMode, Screen, SelectedState, Transition};
+use crate::{cli, env, App};
+
+static LOG_TAIL: usize = 200;
+static LOG_LIMIT: usize = 10_000;
+
+/// The services and number of lines to start following logs with.
+pub struct LogsTarget {
+    tail: String,
+    selected: SelectedState,
+    input_mode: InputMode,
+}
+
+impl LogsTarget {
+    pub fn open() -> Box<dyn Screen> {
+        Box::new(LogsTarget {
+            tail: String::new(),
+            selected: SelectedState::new(),
+            input_mode: InputMode::Normal,
+        })
+    }
+}
+
+impl Screen for LogsTarget {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(3);
+        match self.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+                KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+                KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+                KeyCode::Enter | KeyCode::Char('e') => match self.selected.selected() {
+                    0 | 1 => self.input_mode = InputMode::Insert,
+                    2 => {
+                        let tail = self.tail.parse().ok();
+                        return Some(Transition::Push(LogsView::follow(app, tail)));
+                    }
+                    _ => unreachable!(),
+                },
+                _ => (),
+            },
+            InputMode::Insert => match self.selected.selected() {
+                0 => {
+                    if !edit_text(&mut app.user_profile.target, key) {
+                        self.input_mode = InputMode::Normal;
+                    }
+                }
+                _ => match key.code {
+                    KeyCode::Enter | KeyCode::Esc => self.input_mode = InputMode::Normal,
+                    KeyCode::Char(c) if c.is_ascii_digit() => self.tail.push(c),
+                    KeyCode::Backspace => {
+                        self.tail.pop();
+                    }
+                    _ => (),
+                },
+            },
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+        let items = [
+            format!("target: {}", app.user_profile.target),
+            format!("tail: {}", self.tail),
+            String::from("Follow"),
+        ];
+        list(
+            f,
+            chunks[0],
+            Span::from("Logs"),
+            &items,
+            &mut self.selected,
+            &self.input_mode,
+        );
+        help(
+            f,
+            app,
+            chunks[1],
+            match self.input_mode {
+                InputMode::Normal => &[
+                    "Leave Empty for All",
+                    "",
+                    "Up/K Down/J - Navigate",
+                    "Enter/E - Select/Edit",
+                    "Esc/Q - Return",
+                ],
+                InputMode::Insert => &["Backspace - Delete", "Enter/Esc - Return"],
+            },
+        );
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
+
+/// Follows the logs of the target services, keeping the last `LOG_LIMIT`
+/// lines to scroll and search through.
+pub struct LogsView {
+    lines: Vec<String>,
+    job: Option<cli::Job>,
+    scroll: Option<usize>,
+    search: String,
+    message: Option<String>,
+    input_mode: InputMode,
+}
+
+impl LogsView {
+    /// Starts following the logs of `app.user_profile.target` from the last
+    /// `tail` lines.
+    pub fn follow(app: &App, tail: Option<usize>) -> Box<dyn Screen> {
+        let executor = cli::executor::from_profile(&app.user_profile);
+        let job = cli::logs(
+            executor.as_ref(),
+            Some(app.user_profile.target.clone()),
+            tail.unwrap_or(LOG_TAIL),
+        );
+        Box::new(LogsView {
+            lines: Vec::new(),
+            job: Some(job),
+            scroll: None,
+            search: String::new(),
+            message: None,
+            input_mode: InputMode::Normal,
+        })
+    }
+
+    fn is_paused(&self) -> bool {
+        self.scroll.is_some()
+    }
+
+    /// Freezes the view on the current last line, or resumes following.
+    fn toggle_pause(&mut self) {
+        self.scroll = match self.scroll {
+            Some(_) => None,
+            None => Some(self.lines.len().saturating_sub(1)),
+        };
+    }
+
+    fn scroll_up(&mut self, by: usize) {
+        let last = self.lines.len().saturating_sub(1);
+        self.scroll = Some(self.scroll.unwrap_or(last).saturating_sub(by));
+    }
+
+    fn scroll_down(&mut self, by: usize) {
+        let last = self.lines.len().saturating_sub(1);
+        if let Some(i) = self.scroll {
+            self.scroll = Some((i + by).min(last));
+        }
+    }
+
+    /// Jumps to the closest line containing the search text, older
+    /// (`backwards`) or newer than the current position.
+    fn find(&mut self, backwards: bool) {
+        if self.search.is_empty() {
+            return;
+        }
+        let current = self.scroll.unwrap_or(self.lines.len());
+        let found = match backwards {
+            true => self.lines[..current.min(self.lines.len())]
+                .iter()
+                .rposition(|l| l.contains(&self.search)),
+            false => self
+                .lines
+                .iter()
+                .enumerate()
+                .skip(current + 1)
+                .find(|(_, l)| l.contains(&self.search))
+                .map(|(i, _)| i),
+        };
+        match found {
+            Some(i) => self.scroll = Some(i),
+            None => self.message = Some(format!("no match for \"{}\"", self.search)),
+        }
+    }
+
+    fn save(&mut self, profile: &str) {
+        self.message = Some(match env::save_log(profile, &self.lines) {
+            Ok(path) => format!("saved to {}", path.display()),
+            Err(e) => format!("save failed: {}", e),
+        });
+    }
+}
+
+impl Screen for LogsView {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        match self.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+                KeyCode::Up | KeyCode::Char('k') => self.scroll_up(1),
+                KeyCode::Down | KeyCode::Char('j') => self.scroll_down(1),
+                KeyCode::PageUp => self.scroll_up(10),
+                KeyCode::PageDown => self.scroll_down(10),
+                KeyCode::End | KeyCode::Char('G') => self.scroll = None,
+                KeyCode::Char('p') | KeyCode::Char(' ') => self.toggle_pause(),
+                KeyCode::Char('/') => {
+                    self.search.clear();
+                    self.input_mode = InputMode::Insert;
+                }
+                KeyCode::Char('n') => self.find(true),
+                KeyCode::Char('N') => self.find(false),
+                KeyCode::Char('w') => self.save(&app.user_profile.profile),
+                _ => (),
+            },
+            InputMode::Insert => match key.code {
+                KeyCode::Enter => {
+                    self.input_mode = InputMode::Normal;
+                    self.find(true);
+                }
+                KeyCode::Esc => self.input_mode = InputMode::Normal,
+                KeyCode::Char(c) => self.search.push(c),
+                KeyCode::Backspace => {
+                    self.search.pop();
+                }
+                _ => (),
+            },
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = Layout::default()
+            .direction(tui::layout::Direction::Vertical)
+            .margin(super::GLOBAL_MARGIN)
+            .constraints([Constraint::Percentage(80), Constraint::Percentage(20)].as_ref())
+            .split(f.size());
+
+        let state = match (&self.message, self.is_paused()) {
+            (Some(message), _) => message.clone(),
+            (None, true) => String::from("paused"),
+            (None, false) => String::from("following"),
+        };
+        let title = match self.input_mode {
+            InputMode::Normal => format!("Logs {} - {}", app.user_profile.profile, state),
+            InputMode::Insert => format!(
+                "Logs {} - search: {}",
+                app.user_profile.profile, self.search
+            ),
+        };
+
+        let block = Block::default().borders(Borders::ALL).title(title);
+
+        let height = chunks[0].height.saturating_sub(2) as usize;
+        let end = match self.scroll {
+            Some(i) => (i + 1).min(self.lines.len()),
+            None => self.lines.len(),
+        };
+        let start = end.saturating_sub(height);
+
+        let lines: Vec<Spans> = self.lines[start..end]
+            .iter()
+            .map(|line| log_line(line, &self.search))
+            .collect();
+
+        f.render_widget(Paragraph::new(lines).block(block), chunks[0]);
+
+        help(
+            f,
+            app,
+            chunks[1],
+            match self.input_mode {
+                InputMode::Normal => &[
+                    "Up/K Down/J PgUp PgDn - Scroll  End/G - Follow  Space/P - Pause",
+                    "/ - Search  N/Shift+N - Older/Newer Match  W - Save",
+                    "Esc/Q - Return",
+                ],
+                InputMode::Insert => &["Backspace - Delete", "Enter - Search  Esc - Return"],
+            },
+        );
+    }
+
+    fn poll(&mut self, _app: &mut App) {
+        let job = match &self.job {
+            Some(job) => job,
+            None => return,
+        };
+        let mut exited = None;
+        while let Ok(output) = job.try_recv() {
+            match output {
+                cli::Output::Stdout(line) | cli::Output::Stderr(line) => self.lines.push(line),
+                cli::Output::Exit(result) => exited = Some(result),
+            }
+        }
+        if self.lines.len() > LOG_LIMIT {
+            let excess = self.lines.len() - LOG_LIMIT;
+            self.lines.drain(..excess);
+            self.scroll = self.scroll.map(|i| i.saturating_sub(excess));
+        }
+        if let Some(result) = exited {
+            self.job = None;
+            self.message = Some(format!(
+                "logs stopped with exit code {}",
+                result.exit_code()
+            ));
+        }
+    }
+}
+
+static SERVICE_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Blue,
+    Color::Green,
+    Color::Yellow,
+    Color::LightRed,
+];
+
+/// Colors the `service-1  |` prefix printed by `compose logs` and highlights
+/// every occurrence of `search` in the line.
+fn log_line<'a>(line: &'a str, search: &str) -> Spans<'a> {
+    let mut spans = Vec::new();
+    match line.split_once(" | ") {
+        Some((prefix, message)) => {
+            let hash = prefix
+                .trim()
+                .bytes()
+                .fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+            let color = SERVICE_COLORS[hash % SERVICE_COLORS.len()];
+            highlight(
+                &mut spans,
+                &line[..prefix.len() + 3],
+                search,
+                Style::default().fg(color),
+            );
+            highlight(&mut spans, message, search, Style::default());
+        }
+        None => highlight(&mut spans, line, search, Style::default()),
+    }
+    Spans::from(spans)
+}
+
+fn highlight<'a>(spans: &mut Vec<Span<'a>>, text: &'a str, search: &str, style: Style) {
+    let mut rest = text;
+    if !search.is_empty() {
+        while let Some(i) = rest.find(search) {
+            spans.push(Span::styled(&rest[..i], style));
+            spans.push(Span::styled(
+                &rest[i..i + search.len()],
+                Style::default().fg(Color::Black).bg(Color::Yellow),
+            ));
+            rest = &rest[i + search.len()..];
+        }
+    }
+    spans.push(Span::styled(rest, style));
+}
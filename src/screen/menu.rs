@@ -0,0 +1,134 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::text::Span;
+
+use super::{
+    exec::ExecTarget,
+    help, layout, list,
+    logs::LogsTarget,
+    options::{DownOptions, UpOptions},
+    profile::Profiles,
+    stats::StatsView,
+    status::StatusTable,
+    target::{ActionTarget, ServiceTarget},
+    Frame, InputMode, ProfilePicker, Screen, SelectedState, Transition,
+};
+use crate::{Action, App, UserProfile};
+
+type Entry = (&'static str, fn(&mut App) -> Box<dyn Screen>);
+
+/// Every menu row and the screen it opens.
+static ENTRIES: [Entry; 13] = [
+    ("Env", Profiles::open),
+    ("Up", |app| {
+        ProfilePicker::open(app, "Up", |app| {
+            ServiceTarget::open(app, "Up", "Options", |app| {
+                app.action = Action::Up;
+                Some(Transition::Push(UpOptions::open()))
+            })
+        })
+    }),
+    ("Down", |app| {
+        ProfilePicker::open(app, "Down", |app| {
+            app.action = Action::Down;
+            DownOptions::open()
+        })
+    }),
+    ("Start", |app| {
+        ProfilePicker::open(app, "Start", |app| {
+            ServiceTarget::open(app, "Start", "Start", |app| {
+                app.action = Action::Start;
+                app.run_action()
+            })
+        })
+    }),
+    ("Stop", |app| {
+        ProfilePicker::open(app, "Stop", |app| {
+            ServiceTarget::open(app, "Stop", "Stop", |app| {
+                app.action = Action::Stop;
+                app.run_action()
+            })
+        })
+    }),
+    ("Restart", |app| {
+        ProfilePicker::open(app, "Restart", |_| ActionTarget::open(Action::Restart))
+    }),
+    ("Pull", |app| {
+        ProfilePicker::open(app, "Pull", |_| ActionTarget::open(Action::Pull))
+    }),
+    ("Build", |app| {
+        ProfilePicker::open(app, "Build", |_| ActionTarget::open(Action::Build))
+    }),
+    ("Ps", |app| {
+        ProfilePicker::open(app, "Ps", |_| ActionTarget::open(Action::Ps))
+    }),
+    ("Status", |app| {
+        ProfilePicker::open(app, "Status", |_| StatusTable::open())
+    }),
+    ("Logs", |app| {
+        ProfilePicker::open(app, "Logs", |_| LogsTarget::open())
+    }),
+    ("Exec", |app| {
+        ProfilePicker::open(app, "Exec", ExecTarget::open)
+    }),
+    ("Stats", |app| {
+        ProfilePicker::open(app, "Stats", |_| StatsView::open())
+    }),
+];
+
+pub struct Menu {
+    selected: SelectedState,
+}
+
+impl Menu {
+    pub fn open() -> Box<dyn Screen> {
+        Box::new(Menu {
+            selected: SelectedState::new(),
+        })
+    }
+}
+
+impl Screen for Menu {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(ENTRIES.len());
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Quit),
+            KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+            KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+            KeyCode::Enter | KeyCode::Char('e') => {
+                app.user_profile = UserProfile::default();
+                let (_, open) = ENTRIES[self.selected.selected()];
+                return Some(Transition::Push(open(app)));
+            }
+            _ => (),
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+        let items: Vec<String> = ENTRIES.iter().map(|(name, _)| name.to_string()).collect();
+        list(
+            f,
+            chunks[0],
+            Span::from("Menu"),
+            &items,
+            &mut self.selected,
+            &InputMode::Normal,
+        );
+        help(
+            f,
+            app,
+            chunks[1],
+            &[
+                "Up/K Down/J - Navigate",
+                "Enter/E - Select",
+                "Esc/Q - Exit",
+                "F2 - Release/Capture Mouse",
+            ],
+        );
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
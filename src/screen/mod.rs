@@ -0,0 +1,718 @@
+pub mod exec;
+pub mod logs;
+pub mod menu;
+pub mod options;
+pub mod output;
+pub mod popup;
+pub mod profile;
+pub mod stats;
+pub mod status;
+pub mod target;
+
+use std::{
+    io::Stdout,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use tui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, List, ListItem, ListState},
+};
+
+use crate::{cli, env, App, UserProfile};
+
+pub type Frame<'a> = tui::Frame<'a, CrosstermBackend<Stdout>>;
+
+static GLOBAL_MARGIN: u16 = 1;
+
+/// Two clicks on the same row within this time activate it.
+static DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// One page of the TUI with its own state. Screens are kept on a
+/// [`Navigator`] stack, so going back shows the previous one as it was left.
+pub trait Screen {
+    /// Reacts to a key press; `None` stays on this screen.
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition>;
+
+    fn render(&mut self, f: &mut Frame, app: &mut App);
+
+    /// Called before every draw while the screen is on top, to pick up the
+    /// output of its background jobs.
+    fn poll(&mut self, _app: &mut App) {}
+
+    /// Called when the screen is shown again after the one above it closed.
+    fn resume(&mut self, _app: &mut App) {}
+
+    /// The list or table rows can be clicked in, if the screen has one.
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        None
+    }
+}
+
+pub enum Transition {
+    Push(Box<dyn Screen>),
+    /// Closes the current screen and opens another in its place.
+    Replace(Box<dyn Screen>),
+    Pop,
+    Quit,
+}
+
+pub struct Navigator {
+    stack: Vec<Box<dyn Screen>>,
+    /// When and on which row the last click landed, to spot double clicks.
+    last_click: Option<(Instant, usize)>,
+}
+
+impl Navigator {
+    pub fn new(root: Box<dyn Screen>) -> Navigator {
+        Navigator {
+            stack: vec![root],
+            last_click: None,
+        }
+    }
+
+    pub fn poll(&mut self, app: &mut App) {
+        if let Some(screen) = self.stack.last_mut() {
+            screen.poll(app);
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, app: &mut App) {
+        app.buttons.clear();
+        if let Some(screen) = self.stack.last_mut() {
+            screen.render(f, app);
+        }
+    }
+
+    pub fn handle_event(&mut self, app: &mut App, key: KeyEvent) {
+        let transition = match self.stack.last_mut() {
+            Some(screen) => screen.handle_event(app, key),
+            None => None,
+        };
+        if let Some(transition) = transition {
+            self.apply(app, transition);
+        }
+    }
+
+    /// Turns a mouse event into the key it stands for: clicking a row
+    /// selects it and a double click activates it like Enter, clicking a
+    /// help entry presses its key and the wheel scrolls like Up and Down.
+    /// The mouse does nothing while a popup is open.
+    pub fn mouse_key(&mut self, app: &App, mouse: MouseEvent) -> Option<KeyEvent> {
+        if app.error.is_some() || app.confirm.is_some() {
+            return None;
+        }
+        let code = match mouse.kind {
+            MouseEventKind::ScrollUp => KeyCode::Up,
+            MouseEventKind::ScrollDown => KeyCode::Down,
+            MouseEventKind::Down(MouseButton::Left) => self.click(app, mouse.column, mouse.row)?,
+            _ => return None,
+        };
+        Some(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    /// Selects the clicked row, returning the key a click stands for, if any.
+    fn click(&mut self, app: &App, column: u16, row: u16) -> Option<KeyCode> {
+        if let Some((_, code)) = app.buttons.iter().find(|(r, _)| contains(*r, column, row)) {
+            self.last_click = None;
+            return Some(*code);
+        }
+        let selected = self.stack.last_mut()?.selection()?;
+        let index = selected.row_at(column, row)?;
+        selected.set_current(index);
+        let last_click = self.last_click.replace((Instant::now(), index));
+        match last_click {
+            Some((at, i)) if i == index && at.elapsed() <= DOUBLE_CLICK => {
+                self.last_click = None;
+                Some(KeyCode::Enter)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn apply(&mut self, app: &mut App, transition: Transition) {
+        match transition {
+            Transition::Push(screen) => self.stack.push(screen),
+            Transition::Replace(screen) => {
+                self.stack.pop();
+                self.stack.push(screen);
+            }
+            Transition::Pop => {
+                self.stack.pop();
+                if let Some(screen) = self.stack.last_mut() {
+                    screen.resume(app);
+                }
+            }
+            Transition::Quit => self.stack.clear(),
+        }
+    }
+
+    /// Whether the last screen has been closed.
+    pub fn is_done(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub enum InputMode {
+    Normal,
+    Insert,
+}
+
+#[derive(Debug, Default)]
+pub struct SelectedState {
+    max: usize,
+    current: ListState,
+    /// Where the rows were last drawn, the first one shown and how many
+    /// there were, to map clicks to rows.
+    area: Rect,
+    offset: usize,
+    len: usize,
+    editing: bool,
+}
+
+impl SelectedState {
+    fn new() -> SelectedState {
+        let mut state = SelectedState::default();
+        state.set_current(0);
+        state
+    }
+
+    fn set_max(&mut self, max: usize) {
+        self.max = max;
+    }
+
+    fn set_current(&mut self, current: usize) {
+        self.current.select(Some(current));
+    }
+
+    fn selected(&self) -> usize {
+        self.current.selected().unwrap_or(0)
+    }
+
+    /// Remembers that `len` rows were drawn in `area`, scrolled the way tui
+    /// scrolls lists and tables to keep the selected row in view.
+    fn track(&mut self, area: Rect, len: usize, editing: bool) {
+        if len > 0 {
+            let height = area.height.max(1) as usize;
+            let selected = self.selected().min(len - 1);
+            let offset = self.offset.min(len - 1);
+            self.offset = match selected {
+                s if s >= offset + height => s + 1 - height,
+                s if s < offset => s,
+                _ => offset,
+            };
+        }
+        self.area = area;
+        self.len = len;
+        self.editing = editing;
+    }
+
+    /// The row drawn at the given terminal cell, unless a row is being
+    /// edited.
+    fn row_at(&self, column: u16, row: u16) -> Option<usize> {
+        if self.editing || !contains(self.area, column, row) {
+            return None;
+        }
+        Some(self.offset + (row - self.area.y) as usize).filter(|i| *i < self.len)
+    }
+
+    fn next(&mut self) {
+        let i = match self.current.selected() {
+            Some(i) => {
+                if i >= self.max.saturating_sub(1) {
+                    i
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.current.select(Some(i));
+    }
+
+    fn prev(&mut self) {
+        let i = match self.current.selected() {
+            Some(i) => {
+                if i == 0 {
+                    0
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        self.current.select(Some(i));
+    }
+}
+
+/// A command run in the background, again every `refresh` if set, such as
+/// `ps` for the status table.
+#[derive(Debug, Default)]
+pub struct Poll {
+    job: Option<cli::Job>,
+    stdout: Vec<String>,
+    /// The last line the running command printed to stderr.
+    stderr: Option<String>,
+    refresh: Option<Duration>,
+    refreshed: Option<Instant>,
+    /// Why the last run failed: its last stderr line or its exit code.
+    error: Option<String>,
+}
+
+impl Poll {
+    fn every(refresh: Duration) -> Poll {
+        Poll {
+            refresh: Some(refresh),
+            ..Poll::default()
+        }
+    }
+
+    fn once(job: cli::Job) -> Poll {
+        let mut poll = Poll::default();
+        poll.start(job);
+        poll
+    }
+
+    fn start(&mut self, job: cli::Job) {
+        self.job = Some(job);
+        self.stdout.clear();
+        self.stderr = None;
+    }
+
+    fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    /// Whether it is time to run the command again.
+    fn is_due(&self) -> bool {
+        !self.is_running()
+            && match (self.refreshed, self.refresh) {
+                (None, _) => true,
+                (Some(refreshed), Some(refresh)) => refreshed.elapsed() >= refresh,
+                (Some(_), None) => false,
+            }
+    }
+
+    /// Runs the command again on the next poll.
+    fn refresh_now(&mut self) {
+        self.refreshed = None;
+    }
+
+    /// Takes in what the command printed so far. Once it has finished
+    /// successfully, returns its stdout lines.
+    fn finish(&mut self, command: &str) -> Option<Vec<String>> {
+        let job = self.job.as_ref()?;
+        let mut result = None;
+        while let Ok(output) = job.try_recv() {
+            match output {
+                cli::Output::Stdout(line) => self.stdout.push(line),
+                cli::Output::Stderr(line) => self.stderr = Some(line),
+                cli::Output::Exit(r) => result = Some(r),
+            }
+        }
+        let result = result?;
+
+        self.job = None;
+        self.refreshed = Some(Instant::now());
+        if !result.success() {
+            self.error = Some(match self.stderr.take() {
+                Some(line) => line,
+                None => format!("{} failed with exit code {}", command, result.exit_code()),
+            });
+            return None;
+        }
+        self.error = None;
+        Some(std::mem::take(&mut self.stdout))
+    }
+
+    /// `name` followed by the error, or by when the command last ran.
+    fn title(&self, name: String) -> Span<'static> {
+        match (&self.error, self.refreshed) {
+            (Some(error), _) => Span::styled(
+                format!("{} - {}", name, error),
+                Style::default().fg(Color::Red),
+            ),
+            (None, Some(refreshed)) => Span::from(format!(
+                "{} - refreshed {}s ago",
+                name,
+                refreshed.elapsed().as_secs()
+            )),
+            (None, None) => Span::from(format!("{} - loading", name)),
+        }
+    }
+}
+
+/// The rows of a table drawn in `area`, below its header row.
+fn table_rows(area: Rect) -> Rect {
+    Rect {
+        y: area.y + 1,
+        height: area.height.saturating_sub(1),
+        ..area
+    }
+}
+
+/// Lists the saved profiles and opens the screen `next` builds for the one
+/// picked, with it loaded into `app.user_profile`.
+pub struct ProfilePicker {
+    title: &'static str,
+    profiles: Vec<String>,
+    selected: SelectedState,
+    next: fn(&mut App) -> Box<dyn Screen>,
+}
+
+impl ProfilePicker {
+    pub fn open(
+        app: &mut App,
+        title: &'static str,
+        next: fn(&mut App) -> Box<dyn Screen>,
+    ) -> Box<dyn Screen> {
+        Box::new(ProfilePicker {
+            title,
+            profiles: app.report(env::load_name()).unwrap_or_default(),
+            selected: SelectedState::new(),
+            next,
+        })
+    }
+}
+
+impl Screen for ProfilePicker {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(self.profiles.len());
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+            KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+            KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+            KeyCode::Enter | KeyCode::Char('e') => {
+                let name = self.profiles.get(self.selected.selected())?.clone();
+                let profile = app.report(env::load(name.clone()))?;
+                app.user_profile.set(name, profile);
+                return Some(Transition::Push((self.next)(app)));
+            }
+            _ => (),
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+        list(
+            f,
+            chunks[0],
+            Span::from(self.title),
+            &self.profiles,
+            &mut self.selected,
+            &InputMode::Normal,
+        );
+        help(
+            f,
+            app,
+            chunks[1],
+            &["Up/K Down/J - Navigate", "Enter/E - Select", "Esc/Q - Exit"],
+        );
+    }
+
+    /// Forgets the profile picked last time and shows profiles added or
+    /// removed since.
+    fn resume(&mut self, app: &mut App) {
+        app.user_profile = UserProfile::default();
+        self.profiles = app.report(env::load_name()).unwrap_or_default();
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
+
+/// Splits the screen into the main area and the help box below it.
+fn layout(f: &Frame) -> Vec<Rect> {
+    Layout::default()
+        .direction(tui::layout::Direction::Vertical)
+        .margin(GLOBAL_MARGIN)
+        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
+        .split(f.size())
+}
+
+/// Draws `items` as a bordered list with the selected row highlighted, and
+/// underlined while it is being edited.
+fn list(
+    f: &mut Frame,
+    area: Rect,
+    title: Span,
+    items: &[String],
+    selected: &mut SelectedState,
+    input_mode: &InputMode,
+) {
+    let block = Block::default().borders(Borders::ALL).title(title);
+    selected.track(
+        block.inner(area),
+        items.len(),
+        matches!(input_mode, InputMode::Insert),
+    );
+
+    let items: Vec<ListItem> = items
+        .iter()
+        .map(|item| {
+            let span = Span::from(item.to_owned());
+            ListItem::new(span).style(Style::default())
+        })
+        .collect();
+
+    let items = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(match input_mode {
+            InputMode::Normal => Modifier::REVERSED,
+            InputMode::Insert => Modifier::REVERSED | Modifier::UNDERLINED,
+        }));
+
+    f.render_stateful_widget(items, area, &mut selected.current);
+}
+
+/// Draws the key help and makes each key in it clickable.
+fn help(f: &mut Frame, app: &mut App, area: Rect, lines: &[&str]) {
+    let block = Block::default().borders(Borders::ALL).title("Help");
+
+    let inner = block.inner(area);
+    for (y, line) in (inner.y..inner.bottom()).zip(lines) {
+        for (start, width, code) in help_keys(line) {
+            let x = inner.x + start as u16;
+            if x < inner.right() {
+                let width = (width as u16).min(inner.right() - x);
+                app.buttons.push((Rect::new(x, y, width, 1), code));
+            }
+        }
+    }
+
+    let items: Vec<ListItem> = lines
+        .iter()
+        .map(|item| {
+            let span = Span::from(item.to_owned());
+            ListItem::new(span).style(Style::default())
+        })
+        .collect();
+
+    let items = List::new(items).block(block);
+
+    f.render_widget(items, area);
+}
+
+/// Finds the keys in a help line such as `Up/K Down/J - Navigate` or
+/// `U - Up  S - Start`, as the column and width of the text to click for
+/// each and the key it presses. An entry with a single key can be clicked
+/// anywhere on it.
+fn help_keys(line: &str) -> Vec<(usize, usize, KeyCode)> {
+    let mut keys = Vec::new();
+    let mut start = 0;
+    for entry in line.split("  ") {
+        if let Some((names, _)) = entry.split_once(" - ") {
+            let groups: Vec<(usize, &str)> = names
+                .split(' ')
+                .scan(start, |column, group| {
+                    let at = *column;
+                    *column += group.chars().count() + 1;
+                    Some((at, group))
+                })
+                .collect();
+            for &(column, group) in &groups {
+                let width = match groups.len() {
+                    1 => entry.chars().count(),
+                    _ => group.chars().count(),
+                };
+                if let Some(code) = key_code(group) {
+                    keys.push((column, width, code));
+                }
+            }
+        }
+        start += entry.chars().count() + 2;
+    }
+    keys
+}
+
+/// The first key of `Up/K`, `Esc/Q` or `Shift+N`.
+fn key_code(group: &str) -> Option<KeyCode> {
+    let name = match group {
+        "/" => "/",
+        _ => group.split('/').next()?,
+    };
+    Some(match name {
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "PgUp" => KeyCode::PageUp,
+        "PgDn" => KeyCode::PageDown,
+        "End" => KeyCode::End,
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Backspace" => KeyCode::Backspace,
+        "Space" => KeyCode::Char(' '),
+        "F2" => KeyCode::F(2),
+        _ => {
+            let mut chars = name.strip_prefix("Shift+").unwrap_or(name).chars();
+            let c = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            match name.starts_with("Shift+") {
+                true => KeyCode::Char(c.to_ascii_uppercase()),
+                false => KeyCode::Char(c.to_ascii_lowercase()),
+            }
+        }
+    })
+}
+
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    (area.x..area.right()).contains(&column) && (area.y..area.bottom()).contains(&row)
+}
+
+/// Applies a key to a text field being edited. Returns false when editing
+/// ends.
+fn edit_text(text: &mut String, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Enter | KeyCode::Esc => return false,
+        KeyCode::Char(c) => text.push(c),
+        KeyCode::Backspace => {
+            text.pop();
+        }
+        _ => (),
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts the keys it got and pops itself on Esc.
+    struct Counter {
+        keys: usize,
+        resumed: usize,
+    }
+
+    impl Screen for Counter {
+        fn handle_event(&mut self, _app: &mut App, key: KeyEvent) -> Option<Transition> {
+            match key.code {
+                KeyCode::Esc => Some(Transition::Pop),
+                KeyCode::Enter => Some(Transition::Push(Box::new(Counter {
+                    keys: 0,
+                    resumed: 0,
+                }))),
+                _ => {
+                    self.keys += 1;
+                    None
+                }
+            }
+        }
+
+        fn render(&mut self, _f: &mut Frame, _app: &mut App) {}
+
+        fn resume(&mut self, app: &mut App) {
+            self.resumed += 1;
+            app.error = Some(format!("{} keys", self.keys));
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, crossterm::event::KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn popping_returns_to_the_previous_screen_as_it_was() {
+        let mut app = App::default();
+        let mut navigator = Navigator::new(Box::new(Counter {
+            keys: 0,
+            resumed: 0,
+        }));
+        navigator.handle_event(&mut app, key(KeyCode::Char('j')));
+        navigator.handle_event(&mut app, key(KeyCode::Char('j')));
+        navigator.handle_event(&mut app, key(KeyCode::Enter));
+        navigator.handle_event(&mut app, key(KeyCode::Char('j')));
+        assert_eq!(navigator.stack.len(), 2);
+
+        navigator.handle_event(&mut app, key(KeyCode::Esc));
+        assert_eq!(navigator.stack.len(), 1);
+        assert_eq!(app.error.as_deref(), Some("2 keys"));
+
+        navigator.handle_event(&mut app, key(KeyCode::Esc));
+        assert!(navigator.is_done());
+    }
+
+    #[test]
+    fn selection_stays_in_bounds() {
+        let mut selected = SelectedState::new();
+        selected.set_max(2);
+        selected.next();
+        selected.next();
+        assert_eq!(selected.selected(), 1);
+        selected.prev();
+        selected.prev();
+        assert_eq!(selected.selected(), 0);
+    }
+
+    #[test]
+    fn finds_the_keys_in_help_lines() {
+        assert_eq!(
+            help_keys("Up/K Down/J - Navigate"),
+            vec![(0, 4, KeyCode::Up), (5, 6, KeyCode::Down)]
+        );
+        assert_eq!(
+            help_keys("U - Up  S - Start"),
+            vec![(0, 6, KeyCode::Char('u')), (8, 9, KeyCode::Char('s'))]
+        );
+        assert_eq!(
+            help_keys("/ - Search  N/Shift+N - Older/Newer Match"),
+            vec![(0, 10, KeyCode::Char('/')), (12, 29, KeyCode::Char('n'))]
+        );
+        assert!(help_keys("Leave Empty for All").is_empty());
+    }
+
+    #[test]
+    fn maps_clicks_to_scrolled_rows() {
+        let mut selected = SelectedState::new();
+        selected.track(Rect::new(2, 2, 10, 3), 8, false);
+        assert_eq!(selected.row_at(3, 2), Some(0));
+        assert_eq!(selected.row_at(3, 5), None);
+
+        selected.set_current(6);
+        selected.track(Rect::new(2, 2, 10, 3), 8, false);
+        assert_eq!(selected.row_at(3, 2), Some(4));
+        assert_eq!(selected.row_at(11, 4), Some(6));
+        assert_eq!(selected.row_at(12, 4), None);
+
+        selected.track(Rect::new(2, 2, 10, 3), 8, true);
+        assert_eq!(selected.row_at(3, 2), None);
+    }
+
+    #[test]
+    fn polls_keep_stdout_or_the_error() {
+        use cli::{executor::Recording, Executor};
+
+        let executor = Recording {
+            lines: vec![String::from("web")],
+            code: Some(0),
+            ..Recording::default()
+        };
+        let mut poll = Poll::every(Duration::from_secs(60));
+        assert!(poll.is_due());
+        poll.start(executor.compose(&[]));
+        assert!(!poll.is_due());
+        assert_eq!(poll.finish("ps"), Some(vec![String::from("web")]));
+        assert!(!poll.is_due());
+        poll.refresh_now();
+        assert!(poll.is_due());
+
+        let failing = Recording {
+            code: Some(1),
+            ..Recording::default()
+        };
+        let mut poll = Poll::once(failing.compose(&[]));
+        assert_eq!(poll.finish("config"), None);
+        assert_eq!(
+            poll.error.as_deref(),
+            Some("config failed with exit code 1")
+        );
+        assert!(!poll.is_due());
+    }
+}
@@ -0,0 +1,277 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    layout::{Constraint, Layout, Rect},
+    text::Span,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use super::{help, layout, list, Frame, InputMode, Screen, SelectedState, Transition};
+use crate::App;
+
+fn on_off(on: bool) -> &'static str {
+    match on {
+        true => "on",
+        false => "off",
+    }
+}
+
+/// Splits the form area to leave room for the command preview below it.
+fn form(area: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(tui::layout::Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+        .split(area)
+}
+
+fn preview(f: &mut Frame, area: Rect, app: &App) {
+    let preview = Paragraph::new(app.preview_action().unwrap_or_default())
+        .block(Block::default().borders(Borders::ALL).title("Command"));
+    f.render_widget(preview, area);
+}
+
+/// Flags for `up`, remembered in the profile when it runs.
+pub struct UpOptions {
+    selected: SelectedState,
+    input_mode: InputMode,
+}
+
+impl UpOptions {
+    pub fn open() -> Box<dyn Screen> {
+        Box::new(UpOptions {
+            selected: SelectedState::new(),
+            input_mode: InputMode::Normal,
+        })
+    }
+}
+
+impl Screen for UpOptions {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(8);
+        let options = &mut app.user_profile.config.up;
+        match self.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+                KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+                KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+                KeyCode::Enter | KeyCode::Char('e') | KeyCode::Char(' ') => {
+                    match self.selected.selected() {
+                        0 => options.detach = !options.detach,
+                        1 => {
+                            options.build = match options.build {
+                                None => Some(true),
+                                Some(true) => Some(false),
+                                Some(false) => None,
+                            };
+                        }
+                        2 => options.force_recreate = !options.force_recreate,
+                        3 => options.no_deps = !options.no_deps,
+                        4 => {
+                            options.pull = match options.pull.as_deref() {
+                                None => Some(String::from("always")),
+                                Some("always") => Some(String::from("missing")),
+                                Some("missing") => Some(String::from("never")),
+                                _ => None,
+                            };
+                        }
+                        5 => options.wait = !options.wait,
+                        6 => self.input_mode = InputMode::Insert,
+                        7 => {
+                            if key.code != KeyCode::Char(' ') {
+                                options.scale.retain(|s| !s.trim().is_empty());
+                                return app.save_and_run_action();
+                            }
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                _ => (),
+            },
+            InputMode::Insert => {
+                // Scale is edited as one `;`-separated line of `service=replicas`.
+                let mut text = options.scale.join(";");
+                match key.code {
+                    KeyCode::Enter | KeyCode::Esc => self.input_mode = InputMode::Normal,
+                    KeyCode::Char(c) => text.push(c),
+                    KeyCode::Backspace => {
+                        text.pop();
+                    }
+                    _ => (),
+                }
+                options.scale = match text.is_empty() {
+                    true => Vec::new(),
+                    false => text.split(';').map(String::from).collect(),
+                };
+            }
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+        let form = form(chunks[0]);
+
+        let options = &app.user_profile.config.up;
+        let items = [
+            format!("detach: {}", on_off(options.detach)),
+            format!(
+                "build: {}",
+                match options.build {
+                    Some(true) => "always",
+                    Some(false) => "never",
+                    None => "if missing",
+                }
+            ),
+            format!("force recreate: {}", on_off(options.force_recreate)),
+            format!("no deps: {}", on_off(options.no_deps)),
+            format!("pull: {}", options.pull.as_deref().unwrap_or("default")),
+            format!("wait: {}", on_off(options.wait)),
+            format!("scale: {}", options.scale.join(";")),
+            String::from("Up"),
+        ];
+        list(
+            f,
+            form[0],
+            Span::from("Up"),
+            &items,
+            &mut self.selected,
+            &self.input_mode,
+        );
+        preview(f, form[1], app);
+        help(
+            f,
+            app,
+            chunks[1],
+            match self.input_mode {
+                InputMode::Normal => &[
+                    "Options are saved to the profile when Up runs",
+                    "",
+                    "Up/K Down/J - Navigate",
+                    "Enter/E/Space - Change/Edit",
+                    "Esc/Q - Return",
+                ],
+                InputMode::Insert => &[
+                    "service=replicas;...",
+                    "Backspace - Delete",
+                    "Enter/Esc - Return",
+                ],
+            },
+        );
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
+
+/// Cleanup flags for `down`.
+pub struct DownOptions {
+    selected: SelectedState,
+    input_mode: InputMode,
+}
+
+impl DownOptions {
+    pub fn open() -> Box<dyn Screen> {
+        Box::new(DownOptions {
+            selected: SelectedState::new(),
+            input_mode: InputMode::Normal,
+        })
+    }
+}
+
+impl Screen for DownOptions {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(5);
+        let options = &mut app.user_profile.down;
+        match self.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+                KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+                KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+                KeyCode::Enter | KeyCode::Char('e') | KeyCode::Char(' ') => {
+                    match self.selected.selected() {
+                        0 => {
+                            options.rmi = match options.rmi.as_deref() {
+                                None => Some(String::from("local")),
+                                Some("local") => Some(String::from("all")),
+                                _ => None,
+                            };
+                        }
+                        1 => options.volumes = !options.volumes,
+                        2 => options.remove_orphans = !options.remove_orphans,
+                        3 => self.input_mode = InputMode::Insert,
+                        4 => {
+                            if key.code != KeyCode::Char(' ') {
+                                return app.run_action();
+                            }
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                _ => (),
+            },
+            InputMode::Insert => match key.code {
+                KeyCode::Enter | KeyCode::Esc => self.input_mode = InputMode::Normal,
+                KeyCode::Char(c) => {
+                    if let Some(d) = c.to_digit(10) {
+                        options.timeout = options
+                            .timeout
+                            .unwrap_or(0)
+                            .checked_mul(10)
+                            .and_then(|t| t.checked_add(d))
+                            .or(options.timeout);
+                    }
+                }
+                KeyCode::Backspace => {
+                    options.timeout = options.timeout.map(|t| t / 10).filter(|t| *t != 0);
+                }
+                _ => (),
+            },
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+        let form = form(chunks[0]);
+
+        let options = &app.user_profile.down;
+        let items = [
+            format!("rmi: {}", options.rmi.as_deref().unwrap_or("none")),
+            format!("volumes: {}", on_off(options.volumes)),
+            format!("remove orphans: {}", on_off(options.remove_orphans)),
+            format!(
+                "timeout: {}",
+                options.timeout.map(|t| t.to_string()).unwrap_or_default()
+            ),
+            String::from("Down"),
+        ];
+        list(
+            f,
+            form[0],
+            Span::from("Down"),
+            &items,
+            &mut self.selected,
+            &self.input_mode,
+        );
+        preview(f, form[1], app);
+        help(
+            f,
+            app,
+            chunks[1],
+            match self.input_mode {
+                InputMode::Normal => &[
+                    "Leave timeout Empty for the Default",
+                    "",
+                    "Up/K Down/J - Navigate",
+                    "Enter/E/Space - Change/Edit",
+                    "Esc/Q - Return",
+                ],
+                InputMode::Insert => &["Backspace - Delete", "Enter/Esc - Return"],
+            },
+        );
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
@@ -0,0 +1,145 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use super::{help, layout, Frame, Screen, Transition};
+use crate::{cli, Action, App, Confirm, Then};
+
+/// Streams the output of a running action. Closing it while the command is
+/// still running stops the command, after asking.
+pub struct Output {
+    action: Action,
+    lines: Vec<String>,
+    result: Option<cli::CommandResult>,
+    receiver: Option<cli::Job>,
+    scroll: Option<usize>,
+}
+
+impl Output {
+    pub fn open(action: Action, receiver: cli::Job) -> Box<dyn Screen> {
+        Box::new(Output {
+            action,
+            lines: Vec::new(),
+            result: None,
+            receiver: Some(receiver),
+            scroll: None,
+        })
+    }
+
+    fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    fn scroll_up(&mut self) {
+        let last = self.lines.len().saturating_sub(1);
+        self.scroll = Some(self.scroll.unwrap_or(last).saturating_sub(1));
+    }
+
+    fn scroll_down(&mut self) {
+        let last = self.lines.len().saturating_sub(1);
+        self.scroll = match self.scroll {
+            Some(i) if i + 1 < last => Some(i + 1),
+            _ => None,
+        };
+    }
+}
+
+impl Screen for Output {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') if !self.is_running() => {
+                return Some(Transition::Pop)
+            }
+            // Popping drops the job, which kills the command.
+            KeyCode::Esc | KeyCode::Char('q') => {
+                app.confirm = Some(Confirm {
+                    command: format!("Stop the running {:?}?", self.action),
+                    then: Then::Pop,
+                    ..Confirm::default()
+                });
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_up(),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_down(),
+            _ => (),
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+
+        let title = match &self.result {
+            None => Span::from(format!(
+                "{:?} {} - running",
+                self.action, app.user_profile.profile
+            )),
+            Some(result) => Span::styled(
+                format!(
+                    "{:?} {} - {} after {:.1}s",
+                    self.action,
+                    app.user_profile.profile,
+                    match result.code {
+                        Some(code) => format!("exit code {}", code),
+                        None => String::from("no exit code"),
+                    },
+                    result.duration.as_secs_f32()
+                ),
+                Style::default().fg(match result.success() {
+                    true => Color::Green,
+                    false => Color::Red,
+                }),
+            ),
+        };
+
+        let block = Block::default().borders(Borders::ALL).title(title);
+
+        let height = chunks[0].height.saturating_sub(2) as usize;
+        let end = match self.scroll {
+            Some(i) => i + 1,
+            None => self.lines.len(),
+        };
+        let start = end.saturating_sub(height);
+
+        let lines: Vec<Spans> = self.lines[start..end]
+            .iter()
+            .map(|line| Spans::from(line.to_owned()))
+            .collect();
+
+        f.render_widget(Paragraph::new(lines).block(block), chunks[0]);
+
+        help(
+            f,
+            app,
+            chunks[1],
+            match self.is_running() {
+                true => &["Up/K Down/J - Scroll", "Esc/Q - Stop"],
+                false => &["Up/K Down/J - Scroll", "Esc/Q - Return"],
+            },
+        );
+    }
+
+    /// Drains pending output and keeps a failed result to print on exit.
+    fn poll(&mut self, app: &mut App) {
+        let receiver = match &self.receiver {
+            Some(receiver) => receiver,
+            None => return,
+        };
+        while let Ok(output) = receiver.try_recv() {
+            match output {
+                cli::Output::Stdout(line) | cli::Output::Stderr(line) => self.lines.push(line),
+                cli::Output::Exit(result) => self.result = Some(result),
+            }
+        }
+        let result = match &self.result {
+            Some(result) => result,
+            None => return,
+        };
+        self.receiver = None;
+        if !result.success() {
+            app.failure = Some(result.clone());
+        }
+    }
+}
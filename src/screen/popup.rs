@@ -0,0 +1,153 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use super::{Frame, Transition, GLOBAL_MARGIN};
+use crate::{env, App};
+
+/// Shows the last error in a popup over the current screen until the next
+/// key press.
+pub fn error(f: &mut Frame, app: &mut App) {
+    let error = match &app.error {
+        Some(error) => error,
+        None => return,
+    };
+    let size = f.size();
+    let width = (error.len() as u16 + 4)
+        .max(30)
+        .min(size.width.saturating_sub(GLOBAL_MARGIN * 2));
+    let inner = width.saturating_sub(2).max(1);
+    let height = ((error.len() as u16).div_ceil(inner) + 4).min(size.height);
+    let area = centered(width, height, size);
+    let paragraph = Paragraph::new(vec![
+        Spans::from(error.to_owned()),
+        Spans::from(""),
+        Spans::from(Span::styled(
+            "press any key to dismiss",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ])
+    .wrap(Wrap { trim: true })
+    .block(
+        Block::default()
+            .title("Error")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// Asks before running the command in `app.confirm`, showing exactly what
+/// will run and where, or before another change that needs confirming.
+pub fn confirm(f: &mut Frame, app: &mut App) {
+    let confirm = match &app.confirm {
+        Some(confirm) => confirm,
+        None => return,
+    };
+    let config = &app.user_profile.config;
+    let host = match config.executor {
+        env::Executor::Local => String::from("this machine"),
+        env::Executor::Ssh => match config.port {
+            Some(port) => format!("{}@{}:{}", config.username, config.hostname, port),
+            None => format!("{}@{}", config.username, config.hostname),
+        },
+    };
+    let label = Style::default().fg(Color::DarkGray);
+
+    let command = Span::styled(
+        confirm.command.clone(),
+        Style::default().add_modifier(Modifier::BOLD),
+    );
+    let is_action = confirm.then.is_action();
+    let mut text = match is_action {
+        true => vec![
+            Spans::from(vec![Span::styled("command: ", label), command]),
+            Spans::from(vec![Span::styled("host:    ", label), Span::from(host)]),
+            Spans::from(vec![
+                Span::styled("path:    ", label),
+                Span::from(config.path.clone()),
+            ]),
+            Spans::from(""),
+        ],
+        false => vec![Spans::from(command), Spans::from("")],
+    };
+    let verb = match is_action {
+        true => "run",
+        false => "confirm",
+    };
+    match confirm.expected.is_empty() {
+        true => text.push(Spans::from(match is_action {
+            true => "Run it? y - Yes, n/Esc - No",
+            false => "y - Yes, n/Esc - No",
+        })),
+        false => {
+            text.push(Spans::from(format!(
+                "`{}` is protected. Type its name and press Enter to {}, Esc to cancel:",
+                confirm.expected, verb
+            )));
+            text.push(Spans::from(Span::styled(
+                format!("> {}", confirm.typed),
+                Style::default().fg(match confirm.typed == confirm.expected {
+                    true => Color::Green,
+                    false => Color::Yellow,
+                }),
+            )));
+        }
+    }
+
+    let size = f.size();
+    let width = 70.min(size.width.saturating_sub(GLOBAL_MARGIN * 2));
+    let inner = width.saturating_sub(2).max(1) as usize;
+    let lines: usize = text.iter().map(|l| l.width().max(1).div_ceil(inner)).sum();
+    let area = centered(width, (lines as u16 + 2).min(size.height), size);
+    let paragraph = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title("Confirm")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// Handles keys while a confirmation is open: `y`, or the profile name and
+/// Enter for protected profiles, goes ahead; Esc cancels.
+pub fn confirm_event(app: &mut App, key: KeyEvent) -> Option<Transition> {
+    let confirm = app.confirm.as_mut()?;
+    let confirmed = match (confirm.expected.is_empty(), key.code) {
+        (_, KeyCode::Esc) | (true, KeyCode::Char('n')) => {
+            app.confirm = None;
+            return None;
+        }
+        (true, KeyCode::Char('y')) => true,
+        (false, KeyCode::Char(c)) => {
+            confirm.typed.push(c);
+            false
+        }
+        (false, KeyCode::Backspace) => {
+            confirm.typed.pop();
+            false
+        }
+        (false, KeyCode::Enter) => confirm.typed == confirm.expected,
+        _ => false,
+    };
+    if !confirmed {
+        return None;
+    }
+    let then = app.confirm.take()?.then;
+    app.confirmed(then)
+}
+
+fn centered(width: u16, height: u16, size: Rect) -> Rect {
+    Rect::new(
+        (size.width.saturating_sub(width)) / 2,
+        (size.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    )
+}
@@ -0,0 +1,397 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::text::Span;
+
+use super::{help, layout, list, Frame, InputMode, Screen, SelectedState, Transition};
+use crate::{env, App, Confirm, Then, UserProfile};
+
+/// Saved profiles, with `<new>` first, to edit or delete.
+pub struct Profiles {
+    profiles: Vec<String>,
+    selected: SelectedState,
+}
+
+impl Profiles {
+    pub fn open(app: &mut App) -> Box<dyn Screen> {
+        let mut profiles = Profiles {
+            profiles: Vec::new(),
+            selected: SelectedState::new(),
+        };
+        profiles.resume(app);
+        Box::new(profiles)
+    }
+}
+
+impl Screen for Profiles {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(self.profiles.len());
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+            KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+            KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+            KeyCode::Enter | KeyCode::Char('e') => match self.selected.selected() {
+                0 => {
+                    let hosts = env::ssh_config::hosts();
+                    return Some(Transition::Push(match hosts.is_empty() {
+                        true => Editor::open(),
+                        false => Import::open(hosts),
+                    }));
+                }
+                u => {
+                    let profile = app.report(env::read(self.profiles[u].clone()))?;
+                    app.user_profile.set(self.profiles[u].clone(), profile);
+                    return Some(Transition::Push(Editor::open()));
+                }
+            },
+            KeyCode::Backspace | KeyCode::Char('d') => match self.selected.selected() {
+                0 => (),
+                u => {
+                    let name = self.profiles[u].clone();
+                    let protected = env::read(name.clone()).is_ok_and(|p| p.protected);
+                    app.confirm = Some(Confirm {
+                        command: format!("Delete the profile `{}`?", name),
+                        expected: match protected {
+                            true => name.clone(),
+                            false => String::new(),
+                        },
+                        then: Then::RemoveProfile(name),
+                        ..Confirm::default()
+                    });
+                }
+            },
+            _ => (),
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+        list(
+            f,
+            chunks[0],
+            Span::from("Env"),
+            &self.profiles,
+            &mut self.selected,
+            &InputMode::Normal,
+        );
+        help(
+            f,
+            app,
+            chunks[1],
+            &[
+                "Up/K Down/J - Navigate",
+                "Enter/E - Select",
+                "Esc/Q - Exit",
+                "Backspace/D - Delete",
+            ],
+        );
+    }
+
+    fn resume(&mut self, app: &mut App) {
+        app.user_profile = UserProfile::default();
+        self.profiles = app.report(env::load_name()).unwrap_or_default();
+        self.profiles.insert(0, "<new>".into());
+        if self.selected.selected() >= self.profiles.len() {
+            self.selected.set_current(0);
+        }
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
+
+/// Picks a `Host` from the ssh config to pre-fill a new profile, or starts
+/// from a blank one.
+pub struct Import {
+    hosts: Vec<env::ssh_config::SshHost>,
+    selected: SelectedState,
+}
+
+impl Import {
+    fn open(hosts: Vec<env::ssh_config::SshHost>) -> Box<dyn Screen> {
+        Box::new(Import {
+            hosts,
+            selected: SelectedState::new(),
+        })
+    }
+}
+
+impl Screen for Import {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(self.hosts.len() + 1);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+            KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+            KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+            KeyCode::Enter | KeyCode::Char('e') => {
+                let selected = self.selected.selected();
+                if let Some(host) = selected.checked_sub(1).map(|i| &self.hosts[i]) {
+                    let config = &mut app.user_profile.config;
+                    app.user_profile.profile = host.alias.clone();
+                    // The alias rather than its HostName, so ssh still
+                    // applies the rest of the entry, such as IdentityFile
+                    // and ProxyJump.
+                    config.hostname = host.alias.clone();
+                    config.username = host.user.clone().unwrap_or_default();
+                    config.port = host.port;
+                }
+                return Some(Transition::Replace(Editor::open()));
+            }
+            _ => (),
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+        let mut items = vec![String::from("<blank>")];
+        items.extend(self.hosts.iter().map(|host| {
+            let mut item = format!("{} - ", host.alias);
+            if let Some(user) = &host.user {
+                item.push_str(&format!("{}@", user));
+            }
+            item.push_str(&host.hostname);
+            if let Some(port) = host.port {
+                item.push_str(&format!(":{}", port));
+            }
+            item
+        }));
+        list(
+            f,
+            chunks[0],
+            Span::from("Import from ~/.ssh/config"),
+            &items,
+            &mut self.selected,
+            &InputMode::Normal,
+        );
+        help(
+            f,
+            app,
+            chunks[1],
+            &[
+                "Up/K Down/J - Navigate",
+                "Enter/E - Select",
+                "Esc/Q - Return",
+            ],
+        );
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
+
+/// Edits every field of `app.user_profile` and saves it.
+pub struct Editor {
+    selected: SelectedState,
+    input_mode: InputMode,
+}
+
+impl Editor {
+    fn open() -> Box<dyn Screen> {
+        Box::new(Editor {
+            selected: SelectedState::new(),
+            input_mode: InputMode::Normal,
+        })
+    }
+}
+
+impl Screen for Editor {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(15);
+        match self.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+                KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+                KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+                KeyCode::Enter | KeyCode::Char('e') => match self.selected.selected() {
+                    0..=11 => self.input_mode = InputMode::Insert,
+                    12 => {
+                        app.user_profile.config.executor = match app.user_profile.config.executor {
+                            env::Executor::Local => env::Executor::Ssh,
+                            env::Executor::Ssh => env::Executor::Local,
+                        };
+                    }
+                    13 => app.user_profile.config.protected = !app.user_profile.config.protected,
+                    _ => {
+                        if app.user_profile.profile.is_empty() {
+                            return None;
+                        }
+                        let config = &mut app.user_profile.config;
+                        for list in [
+                            &mut config.ssh_options,
+                            &mut config.compose_files,
+                            &mut config.compose_profiles,
+                            &mut config.env_files,
+                        ] {
+                            list.retain(|o| !o.trim().is_empty());
+                        }
+                        let profile = &app.user_profile;
+                        let valid = profile.config.validate(&profile.profile);
+                        app.report(valid)?;
+                        return confirm_save(app, true);
+                    }
+                },
+                _ => (),
+            },
+            InputMode::Insert => {
+                let selected = self.selected.selected();
+                match key.code {
+                    KeyCode::Enter | KeyCode::Esc => self.input_mode = InputMode::Normal,
+                    KeyCode::Char(c) => edit_profile(&mut app.user_profile, selected, Some(c)),
+                    KeyCode::Backspace => edit_profile(&mut app.user_profile, selected, None),
+                    _ => (),
+                }
+            }
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+        let config = &app.user_profile.config;
+        let items = [
+            format!("profile: {}", app.user_profile.profile),
+            format!("username: {}", config.username),
+            format!("hostname: {}", config.hostname),
+            format!("path: {}", config.path),
+            format!(
+                "port: {}",
+                config.port.map(|p| p.to_string()).unwrap_or_default()
+            ),
+            format!(
+                "identity file: {}",
+                config.identity_file.clone().unwrap_or_default()
+            ),
+            format!(
+                "jump host: {}",
+                config.jump_host.clone().unwrap_or_default()
+            ),
+            format!("ssh options: {}", config.ssh_options.join(";")),
+            format!("compose files: {}", config.compose_files.join(";")),
+            format!(
+                "project name: {}",
+                config.project_name.clone().unwrap_or_default()
+            ),
+            format!("compose profiles: {}", config.compose_profiles.join(";")),
+            format!("env files: {}", config.env_files.join(";")),
+            format!("executor: {}", config.executor),
+            format!(
+                "protected: {}",
+                match config.protected {
+                    true => "yes",
+                    false => "no",
+                }
+            ),
+            String::from("save"),
+        ];
+        list(
+            f,
+            chunks[0],
+            Span::from("Env"),
+            &items,
+            &mut self.selected,
+            &self.input_mode,
+        );
+        help(
+            f,
+            app,
+            chunks[1],
+            match self.input_mode {
+                InputMode::Normal => &[
+                    "Up/K Down/J - Navigate",
+                    "Enter/E - Select/Edit",
+                    "Esc/Q - Return",
+                ],
+                InputMode::Insert => &["Backspace - Delete", "Enter/Esc - Return"],
+            },
+        );
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
+
+/// Writes the profile being edited, first asking for its name if the saved
+/// profile is protected, so turning protection off needs it as well.
+/// Closes the current screen afterwards if `close` is set.
+pub fn confirm_save(app: &mut App, close: bool) -> Option<Transition> {
+    let profile = &app.user_profile;
+    let protected = env::read(profile.profile.clone()).is_ok_and(|p| p.protected);
+    if !protected {
+        return save(app, close);
+    }
+    app.confirm = Some(Confirm {
+        command: format!("Save changes to `{}`?", profile.profile),
+        expected: profile.profile.clone(),
+        then: Then::SaveProfile { close },
+        ..Confirm::default()
+    });
+    None
+}
+
+/// Writes the profile being edited.
+pub fn save(app: &mut App, close: bool) -> Option<Transition> {
+    let profile = &app.user_profile;
+    app.report(env::create(profile.profile.clone(), &profile.config))?;
+    close.then_some(Transition::Pop)
+}
+
+/// Types `c` into, or with `None` deletes the last character of, the
+/// `field`th row of the profile editor.
+fn edit_profile(user_profile: &mut UserProfile, field: usize, c: Option<char>) {
+    let edit = |text: &mut String| match c {
+        Some(c) => text.push(c),
+        None => {
+            text.pop();
+        }
+    };
+    // Lists are edited as one `;`-separated line; empty entries are kept
+    // while typing so a trailing `;` survives.
+    let edit_list = |list: &mut Vec<String>| {
+        let mut text = list.join(";");
+        edit(&mut text);
+        *list = match text.is_empty() {
+            true => Vec::new(),
+            false => text.split(';').map(String::from).collect(),
+        };
+    };
+    let config = &mut user_profile.config;
+    match field {
+        0 => edit(&mut user_profile.profile),
+        1 => edit(&mut config.username),
+        2 => edit(&mut config.hostname),
+        3 => edit(&mut config.path),
+        4 => {
+            config.port = match c {
+                Some(c) => match c.to_digit(10) {
+                    Some(d) => config
+                        .port
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|p| p.checked_add(d as u16))
+                        .or(config.port),
+                    None => config.port,
+                },
+                None => config.port.map(|p| p / 10),
+            }
+            .filter(|p| *p != 0);
+        }
+        5 | 6 | 9 => {
+            let value = match field {
+                5 => &mut config.identity_file,
+                6 => &mut config.jump_host,
+                _ => &mut config.project_name,
+            };
+            let mut text = value.take().unwrap_or_default();
+            edit(&mut text);
+            *value = Some(text).filter(|t| !t.is_empty());
+        }
+        7 => edit_list(&mut config.ssh_options),
+        8 => edit_list(&mut config.compose_files),
+        10 => edit_list(&mut config.compose_profiles),
+        11 => edit_list(&mut config.env_files),
+        _ => unreachable!(),
+    }
+}
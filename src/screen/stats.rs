@@ -0,0 +1,279 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::Duration,
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Sparkline, Table, TableState},
+};
+
+use super::{help, table_rows, Frame, Poll, Screen, SelectedState, Transition, GLOBAL_MARGIN};
+use crate::{cli, App};
+
+static STATS_REFRESH: Duration = Duration::from_secs(2);
+static STATS_HISTORY: usize = 120;
+
+/// Rolling usage history of one container. Network and block I/O are kept
+/// as bytes per second between two samples rather than docker's totals.
+#[derive(Debug, Default)]
+struct History {
+    latest: cli::stats::Sample,
+    cpu: VecDeque<u64>,
+    memory: VecDeque<u64>,
+    net_rx: VecDeque<u64>,
+    net_tx: VecDeque<u64>,
+    block_read: VecDeque<u64>,
+    block_write: VecDeque<u64>,
+}
+
+impl History {
+    fn push(&mut self, sample: cli::stats::Sample, seconds: f64) {
+        let previous = std::mem::replace(&mut self.latest, sample);
+        let sample = &self.latest;
+        let rate = |now: f64, before: f64| match seconds > 0.0 && now >= before {
+            true => ((now - before) / seconds) as u64,
+            false => 0,
+        };
+        let values = [
+            (&mut self.cpu, (sample.cpu * 100.0) as u64),
+            (&mut self.memory, sample.memory as u64),
+            (&mut self.net_rx, rate(sample.net_rx, previous.net_rx)),
+            (&mut self.net_tx, rate(sample.net_tx, previous.net_tx)),
+            (
+                &mut self.block_read,
+                rate(sample.block_read, previous.block_read),
+            ),
+            (
+                &mut self.block_write,
+                rate(sample.block_write, previous.block_write),
+            ),
+        ];
+        for (history, value) in values {
+            if history.len() == STATS_HISTORY {
+                history.pop_front();
+            }
+            history.push_back(value);
+        }
+    }
+}
+
+/// Resource usage of the selected profile's containers, sampled with
+/// `stats --no-stream` every few seconds.
+#[derive(Debug, Default)]
+pub struct StatsView {
+    containers: BTreeMap<String, History>,
+    selected: SelectedState,
+    table: TableState,
+    stats: Poll,
+}
+
+impl StatsView {
+    pub fn open() -> Box<dyn Screen> {
+        Box::new(StatsView {
+            selected: SelectedState::new(),
+            stats: Poll::every(STATS_REFRESH),
+            ..StatsView::default()
+        })
+    }
+}
+
+impl Screen for StatsView {
+    fn handle_event(&mut self, _app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(self.containers.len().max(1));
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+            KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+            KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+            _ => (),
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = Layout::default()
+            .direction(tui::layout::Direction::Vertical)
+            .margin(GLOBAL_MARGIN)
+            .constraints([Constraint::Percentage(85), Constraint::Percentage(15)].as_ref())
+            .split(f.size());
+        let panes = Layout::default()
+            .direction(tui::layout::Direction::Vertical)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+            .split(chunks[0]);
+
+        let title = self
+            .stats
+            .title(format!("Stats {}", app.user_profile.profile));
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let rows = table_rows(block.inner(panes[0]));
+        self.selected.track(rows, self.containers.len(), false);
+
+        let header = Row::new(["Container", "CPU", "Memory", "Net I/O", "Block I/O"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let bytes = cli::stats::format_bytes;
+        let rows: Vec<Row> = self
+            .containers
+            .iter()
+            .map(|(name, history)| {
+                let sample = &history.latest;
+                Row::new(vec![
+                    Cell::from(name.clone()),
+                    Cell::from(format!("{:.2}%", sample.cpu)),
+                    Cell::from(format!(
+                        "{} / {} ({:.1}%)",
+                        bytes(sample.memory),
+                        bytes(sample.memory_limit),
+                        sample.memory_percent
+                    )),
+                    Cell::from(format!(
+                        "{} / {}",
+                        bytes(sample.net_rx),
+                        bytes(sample.net_tx)
+                    )),
+                    Cell::from(format!(
+                        "{} / {}",
+                        bytes(sample.block_read),
+                        bytes(sample.block_write)
+                    )),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(rows)
+            .header(header)
+            .block(block)
+            .widths(&[
+                Constraint::Percentage(25),
+                Constraint::Length(9),
+                Constraint::Percentage(30),
+                Constraint::Percentage(20),
+                Constraint::Percentage(20),
+            ])
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        self.table.select(Some(self.selected.selected()));
+        f.render_stateful_widget(table, panes[0], &mut self.table);
+
+        if let Some(history) = self.containers.values().nth(self.selected.selected()) {
+            let rows = Layout::default()
+                .direction(tui::layout::Direction::Vertical)
+                .constraints([Constraint::Ratio(1, 3); 3].as_ref())
+                .split(panes[1]);
+            let sample = &history.latest;
+            let graphs = [
+                (
+                    format!("CPU {:.2}%", sample.cpu),
+                    &history.cpu,
+                    Some(10_000),
+                    Color::Cyan,
+                ),
+                (
+                    format!("Memory {}", bytes(sample.memory)),
+                    &history.memory,
+                    Some(sample.memory_limit as u64).filter(|limit| *limit > 0),
+                    Color::Magenta,
+                ),
+                (
+                    format!("Net In {}/s", bytes(last(&history.net_rx) as f64)),
+                    &history.net_rx,
+                    None,
+                    Color::Green,
+                ),
+                (
+                    format!("Net Out {}/s", bytes(last(&history.net_tx) as f64)),
+                    &history.net_tx,
+                    None,
+                    Color::Green,
+                ),
+                (
+                    format!("Block Read {}/s", bytes(last(&history.block_read) as f64)),
+                    &history.block_read,
+                    None,
+                    Color::Yellow,
+                ),
+                (
+                    format!("Block Write {}/s", bytes(last(&history.block_write) as f64)),
+                    &history.block_write,
+                    None,
+                    Color::Yellow,
+                ),
+            ];
+            for (i, (title, values, max, color)) in graphs.into_iter().enumerate() {
+                let columns = Layout::default()
+                    .direction(tui::layout::Direction::Horizontal)
+                    .constraints([Constraint::Ratio(1, 2); 2].as_ref())
+                    .split(rows[i / 2]);
+                sparkline(f, title, values, max, color, columns[i % 2]);
+            }
+        }
+
+        help(
+            f,
+            app,
+            chunks[1],
+            &["Up/K Down/J - Select Container", "Esc/Q - Return"],
+        );
+    }
+
+    fn poll(&mut self, app: &mut App) {
+        if self.stats.is_due() {
+            let executor = cli::executor::from_profile(&app.user_profile);
+            self.stats.start(cli::stats_json(executor.as_ref()));
+            return;
+        }
+        // The first sample has nothing to take rates against.
+        let seconds = match self.stats.refreshed {
+            Some(refreshed) => refreshed.elapsed().as_secs_f64(),
+            None => 0.0,
+        };
+        if let Some(lines) = self.stats.finish("stats") {
+            let samples = cli::stats::parse(&lines);
+            self.containers
+                .retain(|name, _| samples.iter().any(|s| &s.name == name));
+            for sample in samples {
+                let history = self.containers.entry(sample.name.clone()).or_default();
+                history.push(sample, seconds);
+            }
+        }
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
+
+fn last(values: &VecDeque<u64>) -> u64 {
+    values.back().copied().unwrap_or(0)
+}
+
+/// Draws the newest values that fit in `area`, scaled to `max` when the
+/// metric has a natural ceiling and to the largest value otherwise.
+fn sparkline(
+    f: &mut Frame,
+    title: String,
+    values: &VecDeque<u64>,
+    max: Option<u64>,
+    color: Color,
+    area: Rect,
+) {
+    let width = area.width.saturating_sub(2) as usize;
+    let data: Vec<u64> = values
+        .iter()
+        .skip(values.len().saturating_sub(width))
+        .copied()
+        .collect();
+    let max = match max {
+        Some(max) => max.max(data.iter().copied().max().unwrap_or(0)),
+        None => data.iter().copied().max().unwrap_or(0).max(1),
+    };
+    let sparkline = Sparkline::default()
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .data(&data)
+        .max(max)
+        .style(Style::default().fg(color));
+
+    f.render_widget(sparkline, area);
+}
@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::{
+    layout::Constraint,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table, TableState},
+};
+
+use super::{
+    help, layout, logs::LogsView, table_rows, Frame, Poll, Screen, SelectedState, Transition,
+};
+use crate::{cli, Action, App};
+
+static STATUS_REFRESH: Duration = Duration::from_secs(5);
+
+/// A table of the profile's services from `ps`, refreshed every few seconds,
+/// with shortcuts to act on the highlighted or checked services.
+#[derive(Debug, Default)]
+pub struct StatusTable {
+    services: Vec<cli::status::Service>,
+    checked: Vec<String>,
+    selected: SelectedState,
+    table: TableState,
+    ps: Poll,
+}
+
+impl StatusTable {
+    pub fn open() -> Box<dyn Screen> {
+        Box::new(StatusTable {
+            selected: SelectedState::new(),
+            ps: Poll::every(STATUS_REFRESH),
+            ..StatusTable::default()
+        })
+    }
+
+    fn toggle(&mut self, index: usize) {
+        let name = match self.services.get(index) {
+            Some(s) => s.service.clone(),
+            None => return,
+        };
+        match self.checked.iter().position(|s| s == &name) {
+            Some(i) => {
+                self.checked.remove(i);
+            }
+            None => self.checked.push(name),
+        }
+    }
+
+    /// Services picked with space, or the highlighted one if none are.
+    fn target(&self) -> String {
+        if !self.checked.is_empty() {
+            return self.checked.join(" ");
+        }
+        self.services
+            .get(self.selected.selected())
+            .map(|s| s.service.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Screen for StatusTable {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        self.selected.set_max(self.services.len().max(1));
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+            KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+            KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+            KeyCode::Char(' ') => self.toggle(self.selected.selected()),
+            KeyCode::Char('r') => self.ps.refresh_now(),
+            KeyCode::Char('l') => {
+                app.user_profile.target = self.target();
+                return Some(Transition::Push(LogsView::follow(app, None)));
+            }
+            KeyCode::Char(c @ ('u' | 's' | 't')) => {
+                let target = self.target();
+                if target.is_empty() {
+                    return None;
+                }
+                app.user_profile.target = target;
+                app.action = match c {
+                    'u' => Action::Up,
+                    's' => Action::Start,
+                    _ => Action::Stop,
+                };
+                return app.run_action();
+            }
+            _ => (),
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+
+        let title = self
+            .ps
+            .title(format!("Status {}", app.user_profile.profile));
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let rows = table_rows(block.inner(chunks[0]));
+        self.selected.track(rows, self.services.len(), false);
+
+        let header = Row::new(["", "Service", "State", "Health", "Ports", "Status"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let rows: Vec<Row> = self
+            .services
+            .iter()
+            .map(|service| {
+                let checked = match self.checked.contains(&service.service) {
+                    true => "[x]",
+                    false => "[ ]",
+                };
+                let color = match service.state.as_str() {
+                    "running" => Color::Green,
+                    "exited" | "dead" => Color::Red,
+                    _ => Color::Yellow,
+                };
+                Row::new(vec![
+                    Cell::from(checked),
+                    Cell::from(service.service.clone()),
+                    Cell::from(service.state.clone()).style(Style::default().fg(color)),
+                    Cell::from(service.health.clone()),
+                    Cell::from(service.ports()),
+                    Cell::from(service.status.clone()),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(rows)
+            .header(header)
+            .block(block)
+            .widths(&[
+                Constraint::Length(3),
+                Constraint::Percentage(20),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Percentage(30),
+                Constraint::Percentage(30),
+            ])
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        self.table.select(Some(self.selected.selected()));
+        f.render_stateful_widget(table, chunks[0], &mut self.table);
+
+        help(
+            f,
+            app,
+            chunks[1],
+            &[
+                "Up/K Down/J - Navigate",
+                "Space - Toggle Service",
+                "U - Up  S - Start  T - Stop",
+                "L - Logs  R - Refresh",
+                "Esc/Q - Return",
+            ],
+        );
+    }
+
+    fn poll(&mut self, app: &mut App) {
+        if self.ps.is_due() {
+            let executor = cli::executor::from_profile(&app.user_profile);
+            self.ps.start(cli::ps_json(executor.as_ref()));
+            return;
+        }
+        if let Some(lines) = self.ps.finish("ps") {
+            self.services = cli::status::parse(&lines);
+            let services = &self.services;
+            self.checked
+                .retain(|name| services.iter().any(|s| &s.service == name));
+        }
+    }
+
+    /// Refreshes right away after an action or the logs close.
+    fn resume(&mut self, _app: &mut App) {
+        self.ps.refresh_now();
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
@@ -0,0 +1,274 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::text::Span;
+
+use super::{
+    edit_text, help, layout, list, Frame, InputMode, Poll, Screen, SelectedState, Transition,
+};
+use crate::{cli, Action, App, UserProfile};
+
+/// Services defined by the selected profile's compose file.
+#[derive(Debug, Default)]
+pub struct ServiceList {
+    pub names: Vec<String>,
+    config: Poll,
+}
+
+impl ServiceList {
+    /// Starts loading the services of `user_profile`.
+    pub fn fetch(user_profile: &UserProfile) -> ServiceList {
+        let executor = cli::executor::from_profile(user_profile);
+        ServiceList {
+            names: Vec::new(),
+            config: Poll::once(cli::config_services(executor.as_ref())),
+        }
+    }
+
+    pub fn poll(&mut self) {
+        let lines = match self.config.finish("config") {
+            Some(lines) => lines,
+            None => return,
+        };
+        self.names = lines
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+        self.names.sort();
+        self.names.dedup();
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.config.is_running()
+    }
+
+    /// First help line: whether the services are still loading or failed.
+    pub fn status(&self, ready: &str, fallback: &str) -> String {
+        match (&self.config.error, self.is_loading()) {
+            (_, true) => String::from("Loading services..."),
+            (Some(error), _) => format!("Could not list services: {}", error),
+            (None, _) if self.names.is_empty() => String::from(fallback),
+            (None, _) => String::from(ready),
+        }
+    }
+}
+
+/// Picks the services for up, start and stop: a checkbox list of the
+/// services in the compose file once they are loaded, free text before that
+/// or if they cannot be listed.
+pub struct ServiceTarget {
+    title: &'static str,
+    action: &'static str,
+    services: ServiceList,
+    selected: SelectedState,
+    input_mode: InputMode,
+    next: fn(&mut App) -> Option<Transition>,
+}
+
+impl ServiceTarget {
+    pub fn open(
+        app: &mut App,
+        title: &'static str,
+        action: &'static str,
+        next: fn(&mut App) -> Option<Transition>,
+    ) -> Box<dyn Screen> {
+        Box::new(ServiceTarget {
+            title,
+            action,
+            services: ServiceList::fetch(&app.user_profile),
+            selected: SelectedState::new(),
+            input_mode: InputMode::Normal,
+            next,
+        })
+    }
+}
+
+impl Screen for ServiceTarget {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        let count = self.services.names.len();
+        let run = count + 1;
+        self.selected.set_max(run + 1);
+        match self.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+                KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+                KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+                KeyCode::Char(' ') | KeyCode::Enter | KeyCode::Char('e') => {
+                    match self.selected.selected() {
+                        u if u == run => {
+                            if key.code != KeyCode::Char(' ') {
+                                return (self.next)(app);
+                            }
+                        }
+                        _ if count == 0 => self.input_mode = InputMode::Insert,
+                        0 => app.user_profile.target.clear(),
+                        u => {
+                            let name = self.services.names[u - 1].clone();
+                            app.user_profile.toggle_service(&name);
+                        }
+                    }
+                }
+                _ => (),
+            },
+            InputMode::Insert => {
+                if !edit_text(&mut app.user_profile.target, key) {
+                    self.input_mode = InputMode::Normal;
+                }
+            }
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+
+        let names = &self.services.names;
+        let target = cli::command::services(&app.user_profile.target);
+        let checkbox = |checked: bool| match checked {
+            true => "[x]",
+            false => "[ ]",
+        };
+
+        let mut items: Vec<String> = Vec::new();
+        if names.is_empty() {
+            items.push(format!("target: {}", app.user_profile.target));
+        } else {
+            items.push(format!("{} all", checkbox(target.is_empty())));
+            for name in names {
+                items.push(format!("{} {}", checkbox(target.contains(name)), name));
+            }
+        }
+        items.push(self.action.to_owned());
+
+        list(
+            f,
+            chunks[0],
+            Span::from(self.title),
+            &items,
+            &mut self.selected,
+            &self.input_mode,
+        );
+
+        let status = self
+            .services
+            .status("Space/Enter - Toggle", "Leave Empty for All");
+        let lines = match self.input_mode {
+            InputMode::Normal => vec![
+                status.as_str(),
+                "",
+                "Up/K Down/J - Navigate",
+                "Enter/E - Select/Edit",
+                "Esc/Q - Return",
+            ],
+            InputMode::Insert => vec!["Backspace - Delete", "Enter/Esc - Return"],
+        };
+        help(f, app, chunks[1], &lines);
+    }
+
+    fn poll(&mut self, _app: &mut App) {
+        self.services.poll();
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
+
+/// Target services typed as text for restart, pull, build and ps, plus
+/// `--no-cache` for build.
+pub struct ActionTarget {
+    action: Action,
+    selected: SelectedState,
+    input_mode: InputMode,
+}
+
+impl ActionTarget {
+    pub fn open(action: Action) -> Box<dyn Screen> {
+        Box::new(ActionTarget {
+            action,
+            selected: SelectedState::new(),
+            input_mode: InputMode::Normal,
+        })
+    }
+
+    fn is_build(&self) -> bool {
+        matches!(self.action, Action::Build)
+    }
+}
+
+impl Screen for ActionTarget {
+    fn handle_event(&mut self, app: &mut App, key: KeyEvent) -> Option<Transition> {
+        let run = match self.is_build() {
+            true => 2,
+            false => 1,
+        };
+        self.selected.set_max(run + 1);
+        match self.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Some(Transition::Pop),
+                KeyCode::Up | KeyCode::Char('k') => self.selected.prev(),
+                KeyCode::Down | KeyCode::Char('j') => self.selected.next(),
+                KeyCode::Enter | KeyCode::Char('e') => match self.selected.selected() {
+                    0 => self.input_mode = InputMode::Insert,
+                    u if u == run => {
+                        app.action = self.action;
+                        return app.run_action();
+                    }
+                    _ => app.user_profile.no_cache = !app.user_profile.no_cache,
+                },
+                _ => (),
+            },
+            InputMode::Insert => {
+                if !edit_text(&mut app.user_profile.target, key) {
+                    self.input_mode = InputMode::Normal;
+                }
+            }
+        }
+        None
+    }
+
+    fn render(&mut self, f: &mut Frame, app: &mut App) {
+        let chunks = layout(f);
+        let title = format!("{:?}", self.action);
+
+        let mut items = vec![format!("target: {}", app.user_profile.target)];
+        if self.is_build() {
+            items.push(format!(
+                "no-cache: {}",
+                match app.user_profile.no_cache {
+                    true => "on",
+                    false => "off",
+                }
+            ));
+        }
+        items.push(title.clone());
+
+        list(
+            f,
+            chunks[0],
+            Span::from(title),
+            &items,
+            &mut self.selected,
+            &self.input_mode,
+        );
+        help(
+            f,
+            app,
+            chunks[1],
+            match self.input_mode {
+                InputMode::Normal => &[
+                    "Leave Empty for All",
+                    "",
+                    "Up/K Down/J - Navigate",
+                    "Enter/E - Select/Edit",
+                    "Esc/Q - Return",
+                ],
+                InputMode::Insert => &["Backspace - Delete", "Enter/Esc - Return"],
+            },
+        );
+    }
+
+    fn selection(&mut self) -> Option<&mut SelectedState> {
+        Some(&mut self.selected)
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crossterm::event::{self, Event};
+
+/// How long the input thread waits for an event before checking whether it
+/// has been paused.
+static INPUT_POLL: Duration = Duration::from_millis(50);
+
+/// Everything the main loop reacts to, in the order it happened.
+#[derive(Debug)]
+pub enum Message {
+    /// A key press, mouse event or resize from the terminal.
+    Input(Event),
+    /// Sent at a fixed rate so views with timers redraw on their own.
+    Tick,
+    /// A background job has new output.
+    Worker,
+    /// Reading the terminal failed; no more input will arrive.
+    Failed(io::Error),
+}
+
+/// Merges terminal input, ticks and wake-ups from background workers into
+/// one channel, each produced by its own thread.
+pub struct Events {
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+    pending: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    acknowledged: Receiver<()>,
+    input: JoinHandle<()>,
+}
+
+impl Events {
+    pub fn new(tick_rate: Duration) -> Events {
+        let (sender, receiver) = mpsc::channel();
+        let paused = Arc::new(AtomicBool::new(false));
+        let (acknowledge, acknowledged) = mpsc::channel();
+
+        let input = {
+            let sender = sender.clone();
+            let paused = Arc::clone(&paused);
+            thread::spawn(move || read_input(sender, &paused, acknowledge))
+        };
+
+        let ticks = sender.clone();
+        thread::spawn(move || loop {
+            thread::sleep(tick_rate);
+            if ticks.send(Message::Tick).is_err() {
+                break;
+            }
+        });
+
+        Events {
+            sender,
+            receiver,
+            pending: Arc::new(AtomicBool::new(false)),
+            paused,
+            acknowledged,
+            input,
+        }
+    }
+
+    /// Blocks until the next message arrives.
+    pub fn next(&self) -> Message {
+        // `self.sender` keeps the channel open, so this never fails.
+        let message = self.receiver.recv().unwrap_or(Message::Tick);
+        if let Message::Worker = message {
+            self.pending.store(false, Ordering::Relaxed);
+        }
+        message
+    }
+
+    /// Returns a function that queues a [`Message::Worker`]. Calls made while
+    /// one is already queued are dropped, so a chatty command cannot flood
+    /// the channel with redraws.
+    pub fn waker(&self) -> impl Fn() + Send + Sync + 'static {
+        let sender = self.sender.clone();
+        let pending = Arc::clone(&self.pending);
+        move || {
+            if !pending.swap(true, Ordering::Relaxed) {
+                sender.send(Message::Worker).unwrap_or(());
+            }
+        }
+    }
+
+    /// Stops reading the terminal while `run` runs, so that a shell or an
+    /// interactive command started from it gets every key.
+    pub fn paused<R>(&self, run: impl FnOnce() -> R) -> R {
+        self.paused.store(true, Ordering::Relaxed);
+        // The thread has exited if this fails, which is just as paused.
+        self.acknowledged.recv().unwrap_or(());
+        let result = run();
+        self.paused.store(false, Ordering::Relaxed);
+        self.input.thread().unpark();
+        result
+    }
+}
+
+impl std::fmt::Debug for Events {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Events").finish_non_exhaustive()
+    }
+}
+
+fn read_input(sender: Sender<Message>, paused: &AtomicBool, acknowledge: Sender<()>) {
+    loop {
+        if paused.load(Ordering::Relaxed) {
+            acknowledge.send(()).unwrap_or(());
+            while paused.load(Ordering::Relaxed) {
+                thread::park();
+            }
+            continue;
+        }
+        let message = match event::poll(INPUT_POLL) {
+            Ok(false) => continue,
+            Ok(true) => match event::read() {
+                Ok(event) => Message::Input(event),
+                Err(e) => Message::Failed(e),
+            },
+            Err(e) => Message::Failed(e),
+        };
+        let failed = matches!(message, Message::Failed(_));
+        if sender.send(message).is_err() || failed {
+            break;
+        }
+    }
+}
@@ -0,0 +1,116 @@
+pub mod events;
+
+use std::{
+    cell::Cell,
+    io, panic,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crossterm::{
+    cursor,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use signal_hook::{consts::SIGTSTP, low_level};
+use tui::{backend::Backend, Terminal};
+
+/// Owns the raw-mode, alternate-screen state of the terminal. Dropping it,
+/// on any return path, hands the terminal back to the shell.
+#[derive(Debug)]
+pub struct Guard {
+    suspend: Arc<AtomicBool>,
+    /// Whether the TUI gets mouse events, or the terminal keeps them for
+    /// selecting and copying text.
+    mouse: Cell<bool>,
+}
+
+impl Guard {
+    pub fn new() -> io::Result<Guard> {
+        install_panic_hook();
+        let suspend = Arc::new(AtomicBool::new(false));
+        signal_hook::flag::register(SIGTSTP, Arc::clone(&suspend))?;
+        let guard = Guard {
+            suspend,
+            mouse: Cell::new(true),
+        };
+        enter(true)?;
+        Ok(guard)
+    }
+
+    /// Whether a SIGTSTP arrived since the last call.
+    pub fn suspend_requested(&self) -> bool {
+        self.suspend.swap(false, Ordering::Relaxed)
+    }
+
+    /// Stops the process the way a shell's Ctrl-Z would, with the terminal
+    /// restored while stopped, and redraws from scratch once resumed.
+    pub fn suspend<B: Backend>(&self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        self.pause(terminal, || low_level::emulate_default_handler(SIGTSTP))?
+    }
+
+    /// Hands the terminal back to the shell while `run` runs, such as for an
+    /// interactive command, then takes it over again and redraws.
+    pub fn pause<B: Backend, R>(
+        &self,
+        terminal: &mut Terminal<B>,
+        run: impl FnOnce() -> R,
+    ) -> io::Result<R> {
+        restore();
+        let result = run();
+        enter(self.mouse.get())?;
+        terminal.clear()?;
+        Ok(result)
+    }
+
+    /// Hands the mouse to the terminal so text can be selected, or takes it
+    /// back.
+    pub fn toggle_mouse(&self) -> io::Result<()> {
+        let mouse = !self.mouse.get();
+        self.mouse.set(mouse);
+        match mouse {
+            true => execute!(io::stdout(), EnableMouseCapture),
+            false => execute!(io::stdout(), DisableMouseCapture),
+        }
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+fn enter(mouse: bool) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    if mouse {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
+    Ok(())
+}
+
+/// Puts the terminal back the way it was before the TUI started. Every step
+/// is attempted even if an earlier one fails.
+pub fn restore() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        cursor::Show
+    );
+}
+
+/// Restores the terminal before the panic message is printed, so it lands
+/// on the normal screen instead of being wiped with the alternate one.
+fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        default_hook(info);
+    }));
+}